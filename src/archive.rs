@@ -0,0 +1,155 @@
+//! Exporting all data about a game into a self-contained JSON file, for preservation and offline analysis
+
+use {
+    std::{
+        ffi::OsString,
+        fmt,
+        fs::{
+            self,
+            File
+        },
+        path::{
+            Path,
+            PathBuf
+        }
+    },
+    serde::{
+        Deserialize,
+        Serialize
+    },
+    serde_json::Value,
+    crate::{
+        Result,
+        client::{
+            AnnotatedData,
+            Client
+        },
+        paginated::PaginatedList
+    }
+};
+
+/// The page size used when downloading runs. This is the maximum allowed by the API.
+const RUNS_PAGE_SIZE: u16 = 200;
+
+/// The raw API data for a game and everything belonging to it.
+///
+/// All resources are stored as returned by the API, so the archive remains usable even if this library's models change.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct GameArchive {
+    /// The game itself.
+    pub game: Value,
+    /// The game's categories, including miscellaneous ones.
+    pub categories: Vec<Value>,
+    /// The game's levels.
+    pub levels: Vec<Value>,
+    /// The game's variables.
+    pub variables: Vec<Value>,
+    /// All runs of the game, regardless of status, in order of submission. Runs without a submission date come first.
+    pub runs: Vec<Value>
+}
+
+/// The state of an interrupted download, stored next to the archive.
+#[derive(Deserialize, Serialize)]
+struct Progress<G = String, A = GameArchive> {
+    game_id: G,
+    archive: A,
+    /// The number of run queries, as returned by `run_queries`, which have been downloaded completely.
+    queries_done: usize,
+    /// The number of runs which have been downloaded for the current run query.
+    query_offset: usize
+}
+
+impl GameArchive {
+    /// Downloads the game with the given ID or abbreviation, along with its categories, levels, variables, and runs, and writes the archive to the given path.
+    ///
+    /// Runs are downloaded separately for each category, and for each level of per-level categories, since the API only allows paginating through the first `MAX_OFFSET` runs of a query.
+    ///
+    /// While downloading, progress is saved to a file next to `path` with `.partial` appended to its name. If a download is interrupted, calling this method again with the same game and path resumes it, skipping the runs which have already been downloaded. The progress file is removed once the archive has been written.
+    ///
+    /// # Errors
+    ///
+    /// If a request fails or an I/O error occurs. Progress made until then is kept. `Error::PaginationLimitReached` is returned if a single full-game category, or a single level of a per-level category, has more than `MAX_OFFSET` runs.
+    pub fn download(client: &Client, game_id: impl fmt::Display, path: impl AsRef<Path>) -> Result<GameArchive> {
+        let path = path.as_ref();
+        let game_id = game_id.to_string();
+        let progress_path = progress_path(path);
+        let (mut archive, mut queries_done, mut query_offset) = match File::open(&progress_path) {
+            Ok(file) => match serde_json::from_reader::<_, Progress>(file) {
+                Ok(progress) if progress.game_id == game_id => (progress.archive, progress.queries_done, progress.query_offset),
+                _ => (GameArchive::default(), 0, 0)
+            },
+            Err(_) => (GameArchive::default(), 0, 0)
+        };
+        if archive.game.is_null() {
+            archive.game = client.get(format!("/games/{}", game_id))?;
+            archive.categories = client.get(format!("/games/{}/categories?miscellaneous=yes", game_id))?;
+            archive.levels = client.get(format!("/games/{}/levels", game_id))?;
+            archive.variables = client.get(format!("/games/{}/variables", game_id))?;
+            save_progress(&progress_path, &game_id, &archive, queries_done, query_offset)?;
+        }
+        for query in run_queries(&archive.game, &archive.categories, &archive.levels).into_iter().skip(queries_done) {
+            let mut runs = PaginatedList::<Value>::with_query(client.clone(), "/runs".into(), query).starting_at(query_offset);
+            runs.set_page_size(RUNS_PAGE_SIZE);
+            for page in runs.pages() {
+                let page = page?;
+                query_offset += page.items.len();
+                archive.runs.extend(page.items.into_iter().map(AnnotatedData::into_data));
+                save_progress(&progress_path, &game_id, &archive, queries_done, query_offset)?;
+            }
+            queries_done += 1;
+            query_offset = 0;
+            save_progress(&progress_path, &game_id, &archive, queries_done, query_offset)?;
+        }
+        // runs were downloaded in chunks, restore the overall order (timestamps in the API's format sort chronologically as strings)
+        archive.runs.sort_by(|run1, run2| run1.get("submitted").and_then(Value::as_str).cmp(&run2.get("submitted").and_then(Value::as_str)));
+        serde_json::to_writer(File::create(path)?, &archive)?;
+        if progress_path.exists() {
+            fs::remove_file(progress_path)?;
+        }
+        Ok(archive)
+    }
+
+    /// Reads an archive previously written by `GameArchive::download`.
+    ///
+    /// # Errors
+    ///
+    /// If an I/O error occurs, or if the file is not a valid archive.
+    pub fn load(path: impl AsRef<Path>) -> Result<GameArchive> {
+        Ok(serde_json::from_reader(File::open(path)?)?)
+    }
+}
+
+/// Returns the queries for the `/runs` endpoint which together return all runs of the given game, each sorted by submission date: one per full-game category, and one per level of each per-level category.
+///
+/// The game, categories, and levels are given as returned by the API. Splitting the runs this way keeps each query below `MAX_OFFSET` for all but the largest categories.
+pub(crate) fn run_queries(game: &Value, categories: &[Value], levels: &[Value]) -> Vec<Vec<(String, String)>> {
+    let id = |value: &Value| value.get("id").and_then(Value::as_str).unwrap_or_default().to_owned();
+    let sorted = |mut query: Vec<(String, String)>| {
+        query.push(("orderby".to_owned(), "submitted".to_owned()));
+        query.push(("direction".to_owned(), "asc".to_owned()));
+        query
+    };
+    let game_id = id(game);
+    let mut queries = Vec::default();
+    for category in categories {
+        if category.get("type").and_then(Value::as_str) == Some("per-level") {
+            for level in levels {
+                queries.push(sorted(vec![("game".to_owned(), game_id.clone()), ("category".to_owned(), id(category)), ("level".to_owned(), id(level))]));
+            }
+        } else {
+            queries.push(sorted(vec![("game".to_owned(), game_id.clone()), ("category".to_owned(), id(category))]));
+        }
+    }
+    queries
+}
+
+fn progress_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().map(OsString::from).unwrap_or_default();
+    file_name.push(".partial");
+    path.with_file_name(file_name)
+}
+
+fn save_progress(progress_path: &Path, game_id: &str, archive: &GameArchive, queries_done: usize, query_offset: usize) -> Result<()> {
+    serde_json::to_writer(File::create(progress_path)?, &Progress { game_id, archive, queries_done, query_offset })?;
+    Ok(())
+}
@@ -5,24 +5,46 @@ use {
         borrow::Borrow,
         collections::HashMap,
         fmt,
-        fs::File,
+        fs::{
+            self,
+            File,
+            OpenOptions
+        },
+        io::{
+            self,
+            Read,
+            Seek,
+            SeekFrom,
+            Write
+        },
         iter::FromIterator,
         marker::PhantomData,
         ops::{
             Range,
             RangeTo
         },
-        path::PathBuf,
+        path::{
+            Path,
+            PathBuf
+        },
         sync::{
             Arc,
-            RwLock
+            Condvar,
+            Mutex,
+            RwLock,
+            atomic::{
+                AtomicUsize,
+                Ordering
+            }
         },
         thread,
         time::{
             Duration,
-            SystemTime
+            SystemTime,
+            UNIX_EPOCH
         }
     },
+    fs4::fs_std::FileExt,
     rand::prelude::*,
     reqwest::{
         self,
@@ -34,9 +56,19 @@ use {
         Serialize,
         de::DeserializeOwned
     },
+    serde_json::value::RawValue,
     url_serde::Serde,
-    crate::Result
+    crate::{
+        Error,
+        Result,
+        embed,
+        util::{
+            self,
+            DeserializeOptions
+        }
+    }
 };
+#[cfg(feature = "vcr")] use crate::vcr::Vcr;
 
 /// The maximum number requests allowed by the API within one `RATE_LIMIT_INTERVAL`. This number is made public for informational purposes only; the `Client` adheres to the rate limit automatically.
 pub const RATE_LIMIT_NUM_REQUESTS: usize = 100;
@@ -44,12 +76,74 @@ pub const RATE_LIMIT_NUM_REQUESTS: usize = 100;
 /// The duration window used for rate limiting. This number is made public for informational purposes only; the `Client` adheres to the rate limit automatically.
 pub const RATE_LIMIT_INTERVAL: Duration = Duration::from_secs(60);
 
-static BASE_URL: &str = "https://www.speedrun.com/api/v1";
+pub(crate) static BASE_URL: &str = "https://www.speedrun.com/api/v1";
+static BASE_PATH: &str = "/api/v1";
+/// How often to check whether reserved requests have been used or released, while waiting for the rate limit because of them.
+const RESERVATION_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// The maximum number of bytes of an error response's body kept in `Error::HttpStatus`.
+const MAX_ERROR_BODY_LEN: usize = 1024;
 
 #[derive(Debug, Deserialize, Serialize)]
 struct RequestInfo {
     timestamp: SystemTime,
-    data: serde_json::Value
+    /// The response body. Kept as raw JSON so it can be deserialized directly into the requested type, and reference-counted so cache hits don't copy it.
+    data: Arc<RawValue>,
+    /// `true` if this entry was not requested directly but embedded in the response to another request, so it doesn't count towards the rate limit.
+    #[serde(default)]
+    embedded: bool
+}
+
+/// The version of the format in which cache entries are stored on disk. This must be increased when the serialization of `RequestInfo` changes, and a migration should be added to `read_cache_file` if possible.
+///
+/// Version 0 is the format used before versioning was introduced, where the cache file contained only the map of entries.
+const CACHE_FORMAT_VERSION: u8 = 1;
+
+/// The contents of a cache file, see `Builder::disk_cache`.
+#[derive(Deserialize, Serialize)]
+struct CacheFile<E = HashMap<String, Box<RawValue>>> {
+    version: u8,
+    entries: E
+}
+
+/// Reads a cache file in any format version up to `CACHE_FORMAT_VERSION`. Entries which can't be read are discarded.
+///
+/// Returns `Ok(None)` if the file was written by a newer version of this library. Such files must not be overwritten, so that the newer version's cache isn't lost.
+///
+/// # Errors
+///
+/// If an I/O error occurs, or if the file is not valid JSON.
+fn read_cache_file(reader: impl Read) -> Result<Option<HashMap<Url, RequestInfo>>> {
+    let raw = serde_json::from_reader::<_, Box<RawValue>>(reader)?;
+    let entries = if let Ok(CacheFile { version, entries }) = serde_json::from_str(raw.get()) {
+        if version > CACHE_FORMAT_VERSION { return Ok(None); }
+        entries
+    } else if let Ok(entries) = serde_json::from_str::<HashMap<String, Box<RawValue>>>(raw.get()) {
+        // version 0, whose entries have the same format as version 1
+        entries
+    } else {
+        return Ok(Some(HashMap::default()));
+    };
+    Ok(Some(entries.into_iter()
+        .filter_map(|(url, info)| Some((Url::parse(&url).ok()?, serde_json::from_str(info.get()).ok()?)))
+        .collect()))
+}
+
+/// The contents of a file in a cache directory, see `Builder::disk_cache_dir`.
+#[derive(Deserialize, Serialize)]
+struct DiskEntry<U = String, I = RequestInfo> {
+    /// The `CACHE_FORMAT_VERSION` this entry was written with. Entries with other versions are ignored.
+    version: u8,
+    /// The full URL, to detect hash collisions.
+    url: U,
+    info: I
+}
+
+/// Returns the path of the file in which the response for the given URL is stored in a cache directory.
+fn entry_path(dir: &Path, url: &Url) -> PathBuf {
+    // FNV-1a, since the hash needs to be stable across Rust versions
+    let hash = url.as_str().bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3));
+    let name = format!("{:016x}", hash);
+    dir.join(&name[..2]).join(format!("{}.json", name))
 }
 
 /// Helper trait implemented on the marker types `NoAuth` and `Auth`.
@@ -131,23 +225,114 @@ impl<T: IntoTimeout> IntoTimeout for Option<T> {
     }
 }
 
-fn timestamp_is_valid(timestamp: SystemTime, timeout: &Range<Duration>) -> bool {
-    timestamp.elapsed().map(|elapsed|
+/// Appends the query to the URL. The query parameters are sorted by key, so that equivalent requests share a cache entry regardless of the order in which the parameters were specified.
+fn request_url<U: IntoUrl, K: AsRef<str>, V: AsRef<str>, Q: IntoIterator>(url: U, query: Q) -> Result<Url>
+where Q::Item: Borrow<(K, V)> {
+    let mut url = url.into_url()?;
+    let mut pairs = url.query_pairs().into_owned().collect::<Vec<_>>();
+    pairs.extend(query.into_iter().map(|pair| {
+        let (key, value) = pair.borrow();
+        (key.as_ref().to_owned(), value.as_ref().to_owned())
+    }));
+    // stable sort to keep the order of repeated keys
+    pairs.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
+    url.query_pairs_mut().clear().extend_pairs(pairs);
+    Ok(url)
+}
+
+fn timestamp_is_valid(now: SystemTime, timestamp: SystemTime, timeout: &Range<Duration>) -> bool {
+    now.duration_since(timestamp).map(|elapsed|
         elapsed < timeout.start
         || elapsed < timeout.end
         && thread_rng().gen_bool((timeout.end - elapsed).as_secs() as f64 / (timeout.end - timeout.start).as_secs() as f64) //TODO use Duration::div_duration when stable
     ).unwrap_or_default()
 }
 
+/// A source of the current time, used by the `Client` for rate limiting and cache expiry, and by `Watcher` and `NotificationPoller` for their polling intervals. Configured using `Builder::clock`.
+///
+/// The default is `SystemClock`. A custom clock can be used in tests to make time pass instantly instead of waiting for the rate limit, for cache entries to expire, or for the next poll.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Returns the current time.
+    fn now(&self) -> SystemTime;
+
+    /// Blocks for the given duration. Called when the client has to wait for the rate limit, or when a poller waits for its next poll.
+    fn sleep(&self, duration: Duration);
+}
+
+/// The default `Clock`, which uses the system time and actually sleeps.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// Which name to use for resources which can have names in multiple languages, like games and users. Configured using `Builder::name_preference`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NamePreference {
+    /// Use the international name.
+    International,
+    /// Use the Japanese name if there is one, and the international name otherwise.
+    Japanese
+}
+
+impl NamePreference {
+    /// Returns the Japanese name if it's preferred and registered, and the international name otherwise.
+    pub(crate) fn choose<'a>(self, international: &'a str, japanese: Option<&'a str>) -> &'a str {
+        match (self, japanese) {
+            (NamePreference::Japanese, Some(japanese)) => japanese,
+            (_, _) => international
+        }
+    }
+}
+
+#[allow(clippy::derivable_impls)] // written out like the crate's other Default impls
+impl Default for NamePreference {
+    fn default() -> NamePreference {
+        NamePreference::International
+    }
+}
+
+/// The priority of a client's requests, configured using `Client::with_priority`.
+///
+/// While a foreground request is waiting for the rate limit or being sent, background requests wait until it's done, so interactive requests aren't stuck behind a long-running background task using a clone of the same client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Priority {
+    /// The default priority, e.g. for requests made in response to user input.
+    Foreground,
+    /// A lower priority, e.g. for crawling or prefetching.
+    Background
+}
+
+#[allow(clippy::derivable_impls)] // written out like the crate's other Default impls
+impl Default for Priority {
+    fn default() -> Priority {
+        Priority::Foreground
+    }
+}
+
 /// A `Client` builder that allows configuring additional settings of the client.
 #[derive(Debug)]
 pub struct Builder<'a, A: AuthType<'a> = NoAuth> {
     user_agent: &'static str,
     api_key: A::Info,
     cache: HashMap<Url, RequestInfo>,
+    cache_dir: Option<PathBuf>,
     cache_path: Option<PathBuf>,
     cache_timeout: Option<Range<Duration>>,
-    num_tries: u8
+    clock: Arc<dyn Clock>,
+    name_preference: NamePreference,
+    num_tries: u8,
+    shared_rate_limit: Option<PathBuf>,
+    deserialize_options: DeserializeOptions,
+    #[cfg(feature = "vcr")]
+    vcr: Option<Vcr>
 }
 
 impl<'a> Builder<'a, NoAuth> {
@@ -156,14 +341,21 @@ impl<'a> Builder<'a, NoAuth> {
     /// For details on the user agent, see the `Client::new` docs.
     ///
     /// For details on the other configuration options, as well as their default values, see the docs on the respective methods.
-    pub fn new(user_agent: &'static str) -> Builder<'_> {
+    pub fn new(user_agent: &'static str) -> Builder<'a> {
         Builder {
             user_agent,
             api_key: (),
             cache: HashMap::default(),
+            cache_dir: None,
             cache_path: None,
             cache_timeout: Some(RATE_LIMIT_INTERVAL..RATE_LIMIT_INTERVAL),
-            num_tries: 1
+            clock: Arc::new(SystemClock),
+            name_preference: NamePreference::default(),
+            num_tries: 1,
+            shared_rate_limit: None,
+            deserialize_options: DeserializeOptions::default(),
+            #[cfg(feature = "vcr")]
+            vcr: None
         }
     }
 
@@ -177,9 +369,16 @@ impl<'a> Builder<'a, NoAuth> {
             user_agent: self.user_agent,
             api_key,
             cache: self.cache,
+            cache_dir: self.cache_dir,
             cache_path: self.cache_path,
             cache_timeout: self.cache_timeout,
-            num_tries: self.num_tries
+            clock: self.clock,
+            name_preference: self.name_preference,
+            num_tries: self.num_tries,
+            shared_rate_limit: self.shared_rate_limit,
+            deserialize_options: self.deserialize_options,
+            #[cfg(feature = "vcr")]
+            vcr: self.vcr
         }
     }
 
@@ -196,11 +395,19 @@ impl<'a> Builder<'a, NoAuth> {
         let mut headers = reqwest::header::HeaderMap::new();
         headers.insert(reqwest::header::USER_AGENT, reqwest::header::HeaderValue::from_static(self.user_agent));
         Ok(Client {
-            cache: Cache::new(self.cache, self.cache_path, self.cache_timeout),
+            cache: Cache::new(self.cache, self.cache_path, self.cache_dir, self.cache_timeout, self.clock, self.shared_rate_limit),
+            name_preference: self.name_preference,
             num_tries: self.num_tries,
             client: reqwest::Client::builder()
                 .default_headers(headers)
                 .build()?,
+            fresh: false,
+            priority: Priority::default(),
+            reservation: None,
+            scheduler: Arc::default(),
+            deserialize_options: self.deserialize_options,
+            #[cfg(feature = "vcr")]
+            vcr: self.vcr.map(Arc::new),
             phantom: PhantomData
         })
     }
@@ -221,11 +428,19 @@ impl<'a> Builder<'a, Auth> {
         headers.insert(reqwest::header::USER_AGENT, reqwest::header::HeaderValue::from_static(self.user_agent));
         headers.insert("X-API-Key", reqwest::header::HeaderValue::from_str(self.api_key)?);
         Ok(Client {
-            cache: Cache::new(self.cache, self.cache_path, self.cache_timeout),
+            cache: Cache::new(self.cache, self.cache_path, self.cache_dir, self.cache_timeout, self.clock, self.shared_rate_limit),
+            name_preference: self.name_preference,
             num_tries: self.num_tries,
             client: reqwest::Client::builder()
                 .default_headers(headers)
                 .build()?,
+            fresh: false,
+            priority: Priority::default(),
+            reservation: None,
+            scheduler: Arc::default(),
+            deserialize_options: self.deserialize_options,
+            #[cfg(feature = "vcr")]
+            vcr: self.vcr.map(Arc::new),
             phantom: PhantomData
         })
     }
@@ -248,23 +463,57 @@ impl<'a, A: AuthType<'a>> Builder<'a, A> {
 
     /// Initializes the cache for API responses from disk.
     ///
-    /// Cache entries older than the currently configured `cache_timeout` are discarded when read, so `cache_timeout` must be called *before* this method to work as expected.
+    /// If the file doesn't exist or is empty, the cache starts out empty, and the file is created when the cache is first written.
+    ///
+    /// Cache entries older than the currently configured `cache_timeout` are discarded when read, so `cache_timeout` must be called *before* this method to work as expected. The same applies to `clock`. Entries which were written in an incompatible format by an older version of this library are discarded as well. If the file was written by a newer version, the cache starts out empty and the file is left unchanged.
+    ///
+    /// The file is locked while it's being read or written, and entries written by other processes in the meantime are merged when the cache is written, so multiple processes can share a cache file.
     ///
     /// # Errors
     ///
-    /// If an I/O error occurs, or if the file is not a valid cache.
+    /// If the file exists but an I/O error occurs while reading it, or if it's not valid JSON.
     pub fn disk_cache(self, cache_path: PathBuf) -> Result<Builder<'a, A>> {
-        let mut cache = serde_json::from_reader::<_, HashMap<Serde<Url>, RequestInfo>>(File::open(&cache_path)?)?;
+        let mut cache = match File::open(&cache_path) {
+            Ok(file) => {
+                // not `file.lock_shared()`, which is `File::lock_shared` on Rust 1.89 and later
+                FileExt::lock_shared(&file)?;
+                if file.metadata()?.len() == 0 { HashMap::default() } else { read_cache_file(&file)?.unwrap_or_default() }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => HashMap::default(),
+            Err(e) => return Err(e.into())
+        };
         if let Some(ref timeout) = self.cache_timeout {
-            cache.retain(|_, req_info| timestamp_is_valid(req_info.timestamp, timeout));
+            cache.retain(|_, req_info| timestamp_is_valid(self.clock.now(), req_info.timestamp, timeout));
         }
         Ok(Builder {
-            cache: cache.into_iter().map(|(url, info)| (url.into_inner(), info)).collect(),
+            cache,
             cache_path: Some(cache_path),
             ..self
         })
     }
 
+    /// Stores API responses in the given directory, creating it if it doesn't exist. Each response is stored in its own file, in subdirectories named after the first characters of a hash of the URL.
+    ///
+    /// Unlike with `disk_cache`, which reads the entire cache up front, responses are only read from the directory when they're requested, so large caches don't slow down startup. New responses are written to the directory immediately. Individual responses can be expired by deleting their files.
+    ///
+    /// # Errors
+    ///
+    /// If the directory can't be created. Requests made by the client return an error if their response can't be written to the directory.
+    pub fn disk_cache_dir(self, cache_dir: PathBuf) -> Result<Builder<'a, A>> {
+        fs::create_dir_all(&cache_dir)?;
+        Ok(Builder {
+            cache_dir: Some(cache_dir),
+            ..self
+        })
+    }
+
+    /// Configures which of the names of games, series, and users are displayed.
+    ///
+    /// The default is `NamePreference::International`.
+    pub fn name_preference(self, name_preference: NamePreference) -> Builder<'a, A> {
+        Builder { name_preference, ..self }
+    }
+
     /// Configures the number of times each request is attempted before a server or network error is returned.
     ///
     /// Client errors are always returned immediately and not retried.
@@ -278,34 +527,148 @@ impl<'a, A: AuthType<'a>> Builder<'a, A> {
         if num_tries == 0 { panic!("0 passed to srcomapi::client::Builder::num_tries"); }
         Builder { num_tries, ..self }
     }
+
+    /// Coordinates the rate limit with other processes on the same machine using the given file, which is created if it doesn't exist.
+    ///
+    /// The API's rate limit applies per IP address, so separate processes each using their own client can exceed it together. If all of them are configured with the same file, the times of their requests are recorded there and each process waits if necessary, so the rate limit is shared between them.
+    ///
+    /// By default, only requests made by clients sharing the same cache are taken into account.
+    pub fn shared_rate_limit(self, path: PathBuf) -> Builder<'a, A> {
+        Builder {
+            shared_rate_limit: Some(path),
+            ..self
+        }
+    }
+
+    /// Configures the clock used for rate limiting and cache expiry, e.g. to control the passage of time in tests.
+    ///
+    /// The default is `SystemClock`.
+    pub fn clock(self, clock: impl Clock + 'static) -> Builder<'a, A> {
+        Builder {
+            clock: Arc::new(clock),
+            ..self
+        }
+    }
+
+    /// Configures whether fields of API responses which aren't known to this library are treated as errors.
+    ///
+    /// This is intended for debugging, to notice when the API changes. Requests for resources with unknown fields fail with a deserialization error naming these fields.
+    ///
+    /// By default, unknown fields are kept and can be accessed using `AnnotatedData::extra`.
+    pub fn strict(self, strict: bool) -> Builder<'a, A> {
+        Builder {
+            deserialize_options: DeserializeOptions { strict, ..self.deserialize_options },
+            ..self
+        }
+    }
+
+    /// Configures whether the JSON returned by the API is kept alongside the deserialized data, so it can be accessed using `AnnotatedData::raw_json`.
+    ///
+    /// This is disabled by default, since it roughly doubles the memory used by each resource.
+    pub fn keep_raw_json(self, keep_raw_json: bool) -> Builder<'a, A> {
+        Builder {
+            deserialize_options: DeserializeOptions { keep_raw_json, ..self.deserialize_options },
+            ..self
+        }
+    }
+
+    /// Records all API requests made by the client, along with their responses, to the given cassette file, which is overwritten if it exists. The cassette can be used with `replay` later.
+    ///
+    /// Responses from the cache are not recorded, so the cache should be configured the same way when replaying. Assets requested using `Client::download_asset` are not recorded either.
+    ///
+    /// This method is only available with the `vcr` feature.
+    ///
+    /// # Errors
+    ///
+    /// If the file can't be created.
+    #[cfg(feature = "vcr")]
+    pub fn record(self, path: impl AsRef<Path>) -> Result<Builder<'a, A>> {
+        Ok(Builder {
+            vcr: Some(Vcr::record(path.as_ref())?),
+            ..self
+        })
+    }
+
+    /// Answers API requests using the responses recorded in the given cassette file instead of sending them. The rate limit is ignored, since no requests are sent.
+    ///
+    /// Each recorded response is replayed at most once. If there is no response for a request which hasn't been replayed yet, the request fails with `Error::UnexpectedRequest`.
+    ///
+    /// This method is only available with the `vcr` feature.
+    ///
+    /// # Errors
+    ///
+    /// If the file can't be read or isn't a valid cassette.
+    #[cfg(feature = "vcr")]
+    pub fn replay(self, path: impl AsRef<Path>) -> Result<Builder<'a, A>> {
+        Ok(Builder {
+            vcr: Some(Vcr::replay(path.as_ref())?),
+            ..self
+        })
+    }
 }
 
 #[derive(Debug)]
 struct Cache {
     data: HashMap<Url, RequestInfo>,
     path: Option<PathBuf>,
+    dir: Option<PathBuf>,
     timeout: Option<Range<Duration>>,
-    changes: u8
+    clock: Arc<dyn Clock>,
+    shared_rate_limit: Option<PathBuf>,
+    changes: u8,
+    /// The number of requests reserved using `Client::reserve_requests` which haven't been made yet.
+    reserved: usize,
+    /// The number of requests which are being sent and don't have a cache entry yet.
+    in_flight: usize
 }
 
 impl Cache {
-    fn new(data: HashMap<Url, RequestInfo>, path: Option<PathBuf>, timeout: Option<Range<Duration>>) -> Arc<RwLock<Cache>> {
+    fn new(data: HashMap<Url, RequestInfo>, path: Option<PathBuf>, dir: Option<PathBuf>, timeout: Option<Range<Duration>>, clock: Arc<dyn Clock>, shared_rate_limit: Option<PathBuf>) -> Arc<RwLock<Cache>> {
         Arc::new(RwLock::new(Cache {
-            data, path, timeout,
-            changes: 0
+            data, path, dir, timeout, clock, shared_rate_limit,
+            changes: 0,
+            reserved: 0,
+            in_flight: 0
         }))
     }
 
-    fn get(&self, url: &Url) -> Option<serde_json::Value> {
+    fn get(&self, url: &Url) -> Option<Arc<RawValue>> {
         if let Some(cache_entry) = self.data.get(url) {
-            if self.timeout.as_ref().map_or(true, |timeout| timestamp_is_valid(cache_entry.timestamp, timeout)) {
-                return Some(cache_entry.data.clone());
+            if self.timeout.as_ref().is_none_or(|timeout| timestamp_is_valid(self.clock.now(), cache_entry.timestamp, timeout)) {
+                return Some(Arc::clone(&cache_entry.data));
             }
         }
         None
     }
 
-    fn insert(&mut self, url: Url, info: RequestInfo) {
+    /// Reads the response for the given URL from the cache directory, if any. The entry is not added to the in-memory cache.
+    fn load(&self, url: &Url) -> Option<RequestInfo> {
+        let dir = self.dir.as_ref()?;
+        let entry = serde_json::from_reader::<_, DiskEntry>(File::open(entry_path(dir, url)).ok()?).ok()?;
+        if entry.version != CACHE_FORMAT_VERSION || entry.url != url.as_str() { return None; }
+        if !self.timeout.as_ref().is_none_or(|timeout| timestamp_is_valid(self.clock.now(), entry.info.timestamp, timeout)) { return None; }
+        Some(entry.info)
+    }
+
+    fn store(&self, url: &Url, info: &RequestInfo) -> Result<()> {
+        if let Some(ref dir) = self.dir {
+            let path = entry_path(dir, url);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            // write to a temporary file first so readers never see a partially written entry
+            let tmp_path = path.with_extension("json.tmp");
+            serde_json::to_writer(File::create(&tmp_path)?, &DiskEntry { version: CACHE_FORMAT_VERSION, url: url.as_str(), info })?;
+            fs::rename(tmp_path, path)?;
+        }
+        Ok(())
+    }
+
+    /// Adds the given response to the cache, and writes it to the cache directory if configured.
+    ///
+    /// The entry is kept in memory even if writing it to the cache directory fails, so the request still counts towards the rate limit.
+    fn insert(&mut self, url: Url, info: RequestInfo) -> Result<()> {
+        let stored = self.store(&url, &info);
         self.data.insert(url, info);
         self.changes += 1;
         if self.changes >= 64 {
@@ -313,22 +676,78 @@ impl Cache {
                 self.changes = 0;
             }
         }
+        stored
     }
 
     fn persist(&self) -> Result<()> {
         if let Some(ref path) = self.path {
-            serde_json::to_writer(File::create(path)?, &self.data.iter().map(|(url, info)| (Serde(url.clone()), info)).collect::<HashMap<_, _>>())?;
+            let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+            // the lock is released when the file is closed
+            file.lock_exclusive()?;
+            // keep entries written by other processes using the same file, unless we have a newer response
+            let on_disk = match read_cache_file(&file) {
+                Ok(Some(on_disk)) => on_disk,
+                // written by a newer version of this library, leave it alone
+                Ok(None) => return Ok(()),
+                Err(_) => HashMap::default()
+            };
+            let mut merged = self.data.iter().map(|(url, info)| (Serde(url.clone()), info)).collect::<HashMap<_, _>>();
+            for (url, info) in &on_disk {
+                if self.data.get(url).is_none_or(|own_info| own_info.timestamp < info.timestamp) {
+                    merged.insert(Serde(url.clone()), info);
+                }
+            }
+            file.set_len(0)?;
+            file.seek(SeekFrom::Start(0))?;
+            serde_json::to_writer(&file, &CacheFile {
+                version: CACHE_FORMAT_VERSION,
+                entries: merged
+            })?;
         }
         Ok(())
     }
 
-    fn rate_limited(&self) -> Result<Option<Duration>> {
-        let recent_request_times = self.data.values().map(|cache_entry| cache_entry.timestamp).filter(|timestamp| timestamp.elapsed().map(|elapsed| elapsed < RATE_LIMIT_INTERVAL).unwrap_or(true)).collect::<Vec<_>>();
-        if recent_request_times.len() >= RATE_LIMIT_NUM_REQUESTS {
-            let elapsed = recent_request_times.iter().min().unwrap().elapsed()?;
-            if elapsed < RATE_LIMIT_INTERVAL {
-                return Ok(Some(RATE_LIMIT_INTERVAL - elapsed));
-            }
+    /// Returns how long to wait until `n` more requests can be made without exceeding the rate limit, taking reserved and in-flight requests into account.
+    fn rate_limited(&self, n: usize) -> Result<Option<Duration>> {
+        let now = self.clock.now();
+        let mut recent_request_times = self.data.values().filter(|cache_entry| !cache_entry.embedded).map(|cache_entry| cache_entry.timestamp).filter(|&timestamp| now.duration_since(timestamp).map(|elapsed| elapsed < RATE_LIMIT_INTERVAL).unwrap_or(true)).collect::<Vec<_>>();
+        let excess = (recent_request_times.len() + self.in_flight + self.reserved + n).saturating_sub(RATE_LIMIT_NUM_REQUESTS);
+        if excess == 0 { return Ok(None); }
+        recent_request_times.sort();
+        Ok(Some(match recent_request_times.get(excess - 1) {
+            Some(&request_time) => RATE_LIMIT_INTERVAL.saturating_sub(now.duration_since(request_time)?),
+            // the budget is held by reservations, which may be used or released at any time
+            None => RESERVATION_POLL_INTERVAL
+        }))
+    }
+
+    /// If a shared rate limit file is configured, records a request in it. If the processes sharing the file have reached the rate limit, nothing is recorded and the time to wait is returned instead.
+    fn reserve_shared(&self) -> Result<Option<Duration>> {
+        let path = match self.shared_rate_limit {
+            Some(ref path) => path,
+            None => return Ok(None)
+        };
+        let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+        // the lock is released when the file is closed
+        file.lock_exclusive()?;
+        let mut contents = String::default();
+        file.read_to_string(&mut contents)?;
+        // the file contains the times of recent requests as milliseconds since the Unix epoch, one per line
+        let now = self.clock.now().duration_since(UNIX_EPOCH)?;
+        let mut request_times = contents.lines()
+            .filter_map(|line| line.parse().ok())
+            .map(Duration::from_millis)
+            .filter(|&request_time| now.saturating_sub(request_time) < RATE_LIMIT_INTERVAL)
+            .collect::<Vec<_>>();
+        if request_times.len() >= RATE_LIMIT_NUM_REQUESTS {
+            let oldest = request_times.iter().min().expect("rate limit is nonzero");
+            return Ok(Some(RATE_LIMIT_INTERVAL - now.saturating_sub(*oldest)));
+        }
+        request_times.push(now);
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        for request_time in request_times {
+            writeln!(file, "{}", request_time.as_millis())?;
         }
         Ok(None)
     }
@@ -340,14 +759,59 @@ impl Drop for Cache {
     }
 }
 
+/// Keeps track of the foreground requests which are waiting for the rate limit or being sent, shared by all clones of a client.
+#[derive(Debug, Default)]
+struct Scheduler {
+    foreground: Mutex<usize>,
+    done: Condvar
+}
+
+impl Scheduler {
+    /// Waits until it's the turn of a request with the given priority. The returned guard must be kept until the request has been sent.
+    fn turn(&self, priority: Priority) -> Turn<'_> {
+        let mut foreground = self.foreground.lock().expect("scheduler lock poisoned");
+        match priority {
+            Priority::Foreground => { *foreground += 1; }
+            Priority::Background => while *foreground > 0 {
+                foreground = self.done.wait(foreground).expect("scheduler lock poisoned");
+            }
+        }
+        Turn { scheduler: self, priority }
+    }
+}
+
+struct Turn<'a> {
+    scheduler: &'a Scheduler,
+    priority: Priority
+}
+
+impl Drop for Turn<'_> {
+    fn drop(&mut self) {
+        if self.priority == Priority::Foreground {
+            *self.scheduler.foreground.lock().expect("scheduler lock poisoned") -= 1;
+            self.scheduler.done.notify_all();
+        }
+    }
+}
+
 /// The entry point to the API.
 ///
-/// The client automatically inserts pauses between requests if necessary according to the API's [rate limits](https://github.com/speedruncomorg/api/blob/master/throttling.md). However, this only works if your application uses the same `Client` for all API requests. If you use multiple `Client`s, you risk getting HTTP `420` errors due to rate limiting.
+/// The client automatically inserts pauses between requests if necessary according to the API's [rate limits](https://github.com/speedruncomorg/api/blob/master/throttling.md). However, this only works if your application uses the same `Client` for all API requests. If you use multiple `Client`s, you risk getting HTTP `420` errors due to rate limiting, unless they share a rate limit file using `Builder::shared_rate_limit`.
 #[derive(Debug, Clone)]
 pub struct Client<A = NoAuth> {
     cache: Arc<RwLock<Cache>>,
+    name_preference: NamePreference,
     num_tries: u8,
     client: reqwest::Client,
+    /// If `true`, existing cache entries are ignored. Responses are still cached.
+    fresh: bool,
+    priority: Priority,
+    /// The number of remaining requests reserved for this client, if it was returned by `Reservation::client`.
+    reservation: Option<Arc<AtomicUsize>>,
+    scheduler: Arc<Scheduler>,
+    deserialize_options: DeserializeOptions,
+    #[cfg(feature = "vcr")]
+    vcr: Option<Arc<Vcr>>,
     phantom: PhantomData<A>
 }
 
@@ -396,54 +860,167 @@ impl Client<Auth> {
 }
 
 impl<A> Client<A> {
+    /// Returns which names this client uses for displaying games, series, and users.
+    pub fn name_preference(&self) -> NamePreference {
+        self.name_preference
+    }
+
+    /// Returns the clock configured using `Builder::clock`.
+    pub(crate) fn clock(&self) -> Arc<dyn Clock> {
+        Arc::clone(&self.cache.read().expect("cache lock poisoned").clock)
+    }
+
     pub(crate) fn get_raw<U: IntoUrl, K: AsRef<str>, V: AsRef<str>, Q: IntoIterator, T: DeserializeOwned>(&self, url: U, query: Q) -> Result<T>
     where Q::Item: Borrow<(K, V)> {
-        let mut url = url.into_url()?;
-        url.query_pairs_mut().extend_pairs(query);
+        let url = request_url(url, query)?;
+        self.get_url(&url).map_err(|e| Error::Request { url, source: Box::new(e) })
+    }
+
+    fn get_url<T: DeserializeOwned>(&self, url: &Url) -> Result<T> {
+        let mut turn = None;
         Ok(loop {
             // check cache
-            if let Some(cache_entry) = self.cache.read().expect("cache lock poisoned").get(&url) {
-                break serde_json::from_value(cache_entry)?;
+            if !self.fresh {
+                // the lock is released before deserializing, so other threads can use the cache in the meantime
+                let cache_entry = self.cache.read().expect("cache lock poisoned").get(url);
+                let cache_entry = cache_entry.or_else(|| self.load_cache_entry(url));
+                if let Some(cache_entry) = cache_entry {
+                    break util::with_options(self.deserialize_options, || serde_json::from_str(cache_entry.get()))?;
+                }
             }
-            // wait for rate limit
+            // wait for pending foreground requests, then for rate limit
+            if turn.is_none() { turn = Some(self.scheduler.turn(self.priority)); }
             let mut cache = self.cache.write().expect("cache lock poisoned");
-            if let Some(rate_limit_timeout) = cache.rate_limited()? {
-                drop(cache);
-                thread::sleep(rate_limit_timeout);
-                continue;
+            // reservations are only changed while holding the cache lock, so this is still accurate when the request is sent
+            let use_reservation = !self.is_replay() && self.reservation.as_ref().is_some_and(|remaining| remaining.load(Ordering::SeqCst) > 0);
+            if !self.is_replay() {
+                let mut rate_limit_timeout = if use_reservation { None } else { cache.rate_limited(1)? };
+                if rate_limit_timeout.is_none() { rate_limit_timeout = cache.reserve_shared()?; }
+                if let Some(rate_limit_timeout) = rate_limit_timeout {
+                    let clock = Arc::clone(&cache.clock);
+                    drop(cache);
+                    // let foreground requests which were started in the meantime go first
+                    if self.priority == Priority::Background { turn = None; }
+                    clock.sleep(rate_limit_timeout);
+                    continue;
+                }
+            }
+            // the reserved request is only used up now, so it isn't lost if the response is cached while waiting for the shared rate limit
+            if use_reservation && self.reservation.as_ref().is_some_and(|remaining| remaining.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |remaining| remaining.checked_sub(1)).is_ok()) {
+                cache.reserved -= 1;
             }
-            // send request
-            let mut response_data = self.client.get(url.clone())
-                .send()
-                .and_then(|resp| resp.error_for_status())
-                .and_then(|mut resp| resp.json::<serde_json::Value>());
+            // send request, without holding the cache lock so other threads can use the cache in the meantime
+            cache.in_flight += 1;
+            drop(cache);
+            let mut response_data = self.send_request(url);
             for _ in 1..self.num_tries {
                 match response_data {
                     Ok(_) => { break; }
-                    Err(e) => if e.is_client_error() || e.is_serialization() { return Err(e.into()); } // return client errors immediately
+                    // return client errors immediately
+                    Err(ref e) if e.http_status().is_some_and(|status| status.is_client_error()) => { break; }
+                    Err(Error::Reqwest(ref e)) if e.is_serialization() => { break; }
+                    Err(_) => {}
+                }
+                response_data = self.send_request(url);
+            }
+            let mut cache = self.cache.write().expect("cache lock poisoned");
+            cache.in_flight -= 1;
+            let mut response_data = response_data?;
+            let now = cache.clock.now();
+            // all entries are added before returning an error from writing one of them to the cache directory
+            let mut stored = Ok(());
+            // move embedded resources into their own cache entries
+            if url.query_pairs().any(|(key, _)| key == "embed") {
+                if let Some(kind) = url.path().strip_prefix(BASE_PATH).and_then(embed::resource_kind) {
+                    // only responses with embeds need to be parsed into a `Value`, to modify them
+                    let mut response_value = serde_json::from_str::<serde_json::Value>(&response_data)?;
+                    if let Some(data) = response_value.get_mut("data") {
+                        for (path, embedded_data) in embed::extract(kind, data) {
+                            stored = stored.and(cache.insert(request_url(&format!("{}{}", BASE_URL, path), Vec::<(String, String)>::default())?, RequestInfo {
+                                timestamp: now,
+                                data: RawValue::from_string(embedded_data.to_string())?.into(),
+                                embedded: true
+                            }));
+                        }
+                    }
+                    response_data = response_value.to_string();
                 }
-                response_data = self.client.get(url.clone())
-                    .send()
-                    .and_then(|resp| resp.error_for_status())
-                    .and_then(|mut resp| resp.json::<serde_json::Value>());
             }
-            let response_data = response_data?;
             // insert response into cache
-            cache.insert(url, RequestInfo {
-                timestamp: SystemTime::now(),
-                data: response_data.clone()
-            });
+            let response_data = Arc::<RawValue>::from(RawValue::from_string(response_data)?);
+            let response = util::with_options(self.deserialize_options, || serde_json::from_str(response_data.get()));
+            stored.and(cache.insert(url.clone(), RequestInfo {
+                timestamp: now,
+                data: response_data,
+                embedded: false
+            }))?;
             // return response
-            break serde_json::from_value(response_data)?;
+            break response?;
         })
     }
 
+    /// Sends a single request to the given URL and returns the response body. Error responses are returned as `Error::HttpStatus`, including the start of their body.
+    fn send_request(&self, url: &Url) -> Result<String> {
+        let (status, mut body) = self.response(url)?;
+        if status.is_client_error() || status.is_server_error() {
+            if body.len() > MAX_ERROR_BODY_LEN {
+                let mut len = MAX_ERROR_BODY_LEN;
+                while !body.is_char_boundary(len) { len -= 1; }
+                body.truncate(len);
+            }
+            return Err(Error::HttpStatus { status, body });
+        }
+        Ok(body)
+    }
+
+    /// Returns the status and body of the response for the given URL, replaying or recording it if configured.
+    fn response(&self, url: &Url) -> Result<(reqwest::StatusCode, String)> {
+        #[cfg(feature = "vcr")] {
+            if let Some(ref vcr) = self.vcr {
+                if let Some(response) = vcr.response(url)? {
+                    return Ok(response);
+                }
+            }
+        }
+        let mut resp = self.client.get(url.clone()).send()?;
+        let status = resp.status();
+        let body = if status.is_client_error() || status.is_server_error() {
+            resp.text().unwrap_or_default()
+        } else {
+            resp.text()?
+        };
+        #[cfg(feature = "vcr")] {
+            if let Some(ref vcr) = self.vcr {
+                vcr.on_response(url, status, &body)?;
+            }
+        }
+        Ok((status, body))
+    }
+
+    #[cfg(feature = "vcr")]
+    fn is_replay(&self) -> bool {
+        self.vcr.as_ref().is_some_and(|vcr| vcr.is_replay())
+    }
+
+    #[cfg(not(feature = "vcr"))]
+    fn is_replay(&self) -> bool {
+        false
+    }
+
+    /// Looks for the response for the given URL in the cache directory, if any, and adds it to the in-memory cache.
+    fn load_cache_entry(&self, url: &Url) -> Option<Arc<RawValue>> {
+        let info = self.cache.read().expect("cache lock poisoned").load(url)?;
+        let data = Arc::clone(&info.data);
+        self.cache.write().expect("cache lock poisoned").data.insert(url.clone(), info);
+        Some(data)
+    }
+
     pub(crate) fn get<U: fmt::Display, T: DeserializeOwned>(&self, url: U) -> Result<T> {
         self.get_abs(&format!("{}{}", BASE_URL, url))
     }
 
     pub(crate) fn get_abs<U: IntoUrl, T: DeserializeOwned>(&self, url: U) -> Result<T> {
-        self.get_abs_query(url, &Vec::<(String, String)>::default())
+        self.get_abs_query(url, Vec::<(String, String)>::default())
     }
 
     pub(crate) fn get_query<U: fmt::Display, K: AsRef<str>, V: AsRef<str>, Q: IntoIterator, T: DeserializeOwned>(&self, url: U, query: Q) -> Result<T>
@@ -458,7 +1035,91 @@ impl<A> Client<A> {
 }
 
 impl<A: Clone> Client<A> {
-    pub(crate) fn annotate<T>(&self, data: T) -> AnnotatedData<T, A> {
+    /// Returns a copy of this client which shares its cache and rate limit, but always sends requests instead of using cached responses.
+    pub(crate) fn fresh(&self) -> Client<A> {
+        Client {
+            fresh: true,
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy of this client which shares its cache and rate limit, but makes requests with the given priority.
+    ///
+    /// Data requested using the returned client is annotated with it, so further requests made through that data use the same priority.
+    pub fn with_priority(&self, priority: Priority) -> Client<A> {
+        Client {
+            priority,
+            ..self.clone()
+        }
+    }
+
+    /// Waits until `n` requests can be made without exceeding the rate limit, then reserves them so that other requests don't use them up.
+    ///
+    /// This can be used before an operation which makes a known number of requests, so that it isn't stalled by the rate limit halfway through. The reserved requests are made using `Reservation::client`. Once they're used up, further requests made through that client are subject to the rate limit as usual. Reserved requests which haven't been made are released when the `Reservation` is dropped.
+    ///
+    /// Responses from the cache don't use up reserved requests. Reservations only apply to clients sharing this client's cache, not to other processes using `Builder::shared_rate_limit`.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is greater than `RATE_LIMIT_NUM_REQUESTS`.
+    pub fn reserve_requests(&self, n: usize) -> Result<Reservation<A>> {
+        if n > RATE_LIMIT_NUM_REQUESTS { panic!("more than RATE_LIMIT_NUM_REQUESTS passed to srcomapi::client::Client::reserve_requests"); }
+        loop {
+            let mut cache = self.cache.write().expect("cache lock poisoned");
+            match cache.rate_limited(n)? {
+                Some(rate_limit_timeout) => {
+                    let clock = Arc::clone(&cache.clock);
+                    drop(cache);
+                    clock.sleep(rate_limit_timeout);
+                }
+                None => {
+                    cache.reserved += n;
+                    break Ok(self.reservation(n));
+                }
+            }
+        }
+    }
+
+    /// Like `reserve_requests`, but returns `Ok(None)` instead of waiting if `n` requests can't be made right now.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is greater than `RATE_LIMIT_NUM_REQUESTS`.
+    pub fn try_reserve_requests(&self, n: usize) -> Result<Option<Reservation<A>>> {
+        if n > RATE_LIMIT_NUM_REQUESTS { panic!("more than RATE_LIMIT_NUM_REQUESTS passed to srcomapi::client::Client::try_reserve_requests"); }
+        let mut cache = self.cache.write().expect("cache lock poisoned");
+        Ok(if cache.rate_limited(n)?.is_none() {
+            cache.reserved += n;
+            Some(self.reservation(n))
+        } else {
+            None
+        })
+    }
+
+    fn reservation(&self, n: usize) -> Reservation<A> {
+        Reservation {
+            client: Client {
+                reservation: Some(Arc::new(AtomicUsize::new(n))),
+                ..self.clone()
+            }
+        }
+    }
+
+    /// Downloads the file at the given URL, such as a game's cover art or trophy, and writes it to the given writer. Returns the number of bytes written.
+    ///
+    /// The request uses this client's configuration like the user agent, but bypasses the cache and the rate limit since it's not an API request.
+    pub fn download_asset(&self, url: &Url, mut writer: impl Write) -> Result<u64> {
+        self.client.get(url.clone())
+            .send()
+            .and_then(|resp| resp.error_for_status())
+            .and_then(|mut resp| resp.copy_to(&mut writer))
+            .map_err(|e| Error::Request { url: url.clone(), source: Box::new(e.into()) })
+    }
+
+    /// Attaches this client to the given data, so that methods requiring API requests can be used.
+    ///
+    /// This is the inverse of `AnnotatedData::into_data`, e.g. for data which was stored or sent to another thread without a client.
+    pub fn annotate<T>(&self, data: T) -> AnnotatedData<T, A> {
         AnnotatedData {
             data,
             client: self.clone()
@@ -475,24 +1136,64 @@ impl<A: Clone> Client<A> {
     }
 }
 
+/// Requests reserved from the rate limit, returned by `Client::reserve_requests`.
+///
+/// The remaining reserved requests are released when this is dropped.
+#[derive(Debug)]
+pub struct Reservation<A = NoAuth> {
+    client: Client<A>
+}
+
+impl<A> Reservation<A> {
+    /// Returns a client which makes the reserved requests. It shares the cache and rate limit of the client this reservation was made with.
+    ///
+    /// Data requested using the returned client is annotated with it, so further requests made through that data use up the reservation as well.
+    pub fn client(&self) -> &Client<A> {
+        &self.client
+    }
+
+    /// Returns the number of reserved requests which haven't been made yet.
+    pub fn remaining(&self) -> usize {
+        self.client.reservation.as_ref().map_or(0, |remaining| remaining.load(Ordering::SeqCst))
+    }
+}
+
+impl<A> Drop for Reservation<A> {
+    fn drop(&mut self) {
+        if let Some(ref remaining) = self.client.reservation {
+            if let Ok(mut cache) = self.client.cache.write() {
+                cache.reserved -= remaining.swap(0, Ordering::SeqCst);
+            }
+        }
+    }
+}
+
 impl From<Client<Auth>> for Client<NoAuth> {
     fn from(auth_client: Client<Auth>) -> Client<NoAuth> {
         Client {
             cache: auth_client.cache,
+            name_preference: auth_client.name_preference,
             num_tries: auth_client.num_tries,
             client: auth_client.client,
+            fresh: auth_client.fresh,
+            priority: auth_client.priority,
+            reservation: auth_client.reservation,
+            scheduler: auth_client.scheduler,
+            deserialize_options: auth_client.deserialize_options,
+            #[cfg(feature = "vcr")]
+            vcr: auth_client.vcr,
             phantom: PhantomData
         }
     }
 }
 
-impl<'a, A: Clone> From<&'a Client<A>> for Client<A> {
+impl<A: Clone> From<&Client<A>> for Client<A> {
     fn from(client_ref: &Client<A>) -> Client<A> {
         client_ref.clone()
     }
 }
 
-impl<'a> From<&'a Client<Auth>> for Client<NoAuth> {
+impl From<&Client<Auth>> for Client<NoAuth> {
     fn from(auth_client_ref: &Client<Auth>) -> Client<NoAuth> {
         Client::<Auth>::from(auth_client_ref).into()
     }
@@ -503,11 +1204,35 @@ struct ResponseData<T> {
     data: T
 }
 
-#[derive(Debug, Deserialize, Clone)]
-pub(crate) struct Link {
-    pub(crate) rel: Option<String>,
+/// A link to a related API resource, as included in most API responses. Returned by `AnnotatedData::links`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Link {
+    /// The kind of relation, e.g. `"self"`, `"game"`, or `"leaderboard"`. Missing for some links, like video links.
+    pub rel: Option<String>,
+    /// The URL of the linked resource.
     #[serde(with = "url_serde")]
-    pub(crate) uri: Url
+    pub uri: Url
+}
+
+/// Implemented on the cached data of resources which can be requested individually. This trait is an implementation detail.
+pub trait Resource: DeserializeOwned {
+    /// The path of the endpoint returning this resource, relative to the API base URL.
+    fn path(&self) -> String;
+}
+
+/// Implemented on the cached data of resources which keep the fields of the API response not known to this library. This trait is an implementation detail.
+pub trait Extensible {
+    /// The fields of the API response which aren't represented by this library's data types.
+    fn extra(&self) -> &serde_json::Map<String, serde_json::Value>;
+
+    /// The API response this data was deserialized from, if `Builder::keep_raw_json` was enabled.
+    fn raw_json(&self) -> Option<&serde_json::Value>;
+}
+
+/// Implemented on the cached data of resources which include links to related resources. This trait is an implementation detail.
+pub trait Linked {
+    /// The links included in the response for this resource.
+    fn links(&self) -> &[Link];
 }
 
 /// This type is an implementation detail.
@@ -519,6 +1244,80 @@ pub struct AnnotatedData<T, A = NoAuth> {
     pub(crate) data: T
 }
 
+impl<T, A> AnnotatedData<T, A> {
+    /// Returns a reference to the cached data, without the client.
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
+    /// Returns the cached data, detached from the client. The data can be serialized, and attached to a client again using `Client::annotate`.
+    pub fn into_data(self) -> T {
+        self.data
+    }
+}
+
+impl<T: Resource, A: Clone> AnnotatedData<T, A> {
+    /// Requests this resource again, bypassing the cache, and replaces the data with the response.
+    ///
+    /// The response is also stored in the cache, so other requests for this resource will use the new data.
+    pub fn refresh(&mut self) -> Result<()> {
+        self.data = self.client.fresh().get(self.data.path())?;
+        Ok(())
+    }
+}
+
+impl<T: Extensible, A> AnnotatedData<T, A> {
+    /// Returns the fields of the API response for this resource which aren't represented by this library's data types, e.g. because they were added to the API recently.
+    ///
+    /// Use `Builder::strict` to get an error when requesting resources with such fields instead.
+    pub fn extra(&self) -> &serde_json::Map<String, serde_json::Value> {
+        self.data.extra()
+    }
+
+    /// Returns the JSON this resource was deserialized from, as returned by the API. This can be used to access fields which aren't supported by this library yet.
+    ///
+    /// Returns `None` unless the client was configured using `Builder::keep_raw_json`, or if the data was attached to the client using `Client::annotate`.
+    pub fn raw_json(&self) -> Option<&serde_json::Value> {
+        self.data.raw_json()
+    }
+}
+
+impl<T: Linked, A: Clone> AnnotatedData<T, A> {
+    /// Returns the links to related resources included in the API response.
+    ///
+    /// This can be used to access related resources for which there is no dedicated method yet, see also `follow_link`.
+    pub fn links(&self) -> &[Link] {
+        self.data.links()
+    }
+
+    fn link(&self, rel: &str) -> Option<&Link> {
+        self.links().iter().find(|link| link.rel.as_ref().is_some_and(|link_rel| link_rel == rel))
+    }
+
+    /// Requests the resource linked with the given relation, e.g. `follow_link::<GameData>("game")`. Returns `Ok(None)` if there is no such link.
+    ///
+    /// For links to lists of resources, use `follow_link_collection` instead.
+    pub fn follow_link<U: DeserializeOwned>(&self, rel: &str) -> Result<Option<AnnotatedData<U, A>>> {
+        self.link(rel)
+            .map(|link| Ok(self.client.annotate(self.client.get_abs(link.uri.clone())?)))
+            .transpose()
+    }
+
+    /// Requests the list of resources linked with the given relation, e.g. `follow_link_collection::<CategoryData, Vec<_>>("categories")`. Returns `Ok(None)` if there is no such link.
+    ///
+    /// For paginated lists, like `"runs"`, only the first page is returned.
+    pub fn follow_link_collection<U: DeserializeOwned, C: FromIterator<AnnotatedData<U, A>>>(&self, rel: &str) -> Result<Option<C>> {
+        self.link(rel)
+            .map(|link| Ok(
+                self.client.get_abs::<_, Vec<_>>(link.uri.clone())?
+                    .into_iter()
+                    .map(|data| self.client.annotate(data))
+                    .collect()
+            ))
+            .transpose()
+    }
+}
+
 impl<T> From<AnnotatedData<T, Auth>> for AnnotatedData<T, NoAuth> {
     fn from(annotated_data: AnnotatedData<T, Auth>) -> AnnotatedData<T> {
         AnnotatedData {
@@ -527,3 +1326,99 @@ impl<T> From<AnnotatedData<T, Auth>> for AnnotatedData<T, NoAuth> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A clock which only advances when the client sleeps or the test calls `advance`.
+    #[derive(Debug, Clone)]
+    struct FakeClock(Arc<Mutex<SystemTime>>);
+
+    impl FakeClock {
+        fn new() -> FakeClock {
+            FakeClock(Arc::new(Mutex::new(UNIX_EPOCH + Duration::from_secs(1_000_000_000))))
+        }
+
+        fn advance(&self, duration: Duration) {
+            *self.0.lock().expect("clock lock poisoned") += duration;
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> SystemTime {
+            *self.0.lock().expect("clock lock poisoned")
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.advance(duration);
+        }
+    }
+
+    fn client(clock: &FakeClock) -> Client {
+        Builder::new("srcomapi-tests").clock(clock.clone()).build().expect("failed to build client")
+    }
+
+    /// Adds cache entries as if the given number of requests had just been made.
+    fn fill_cache(client: &Client, n: usize) -> Result<()> {
+        let mut cache = client.cache.write().expect("cache lock poisoned");
+        let now = cache.clock.now();
+        let start = cache.data.len();
+        for i in start..start + n {
+            cache.insert(Url::parse(&format!("{}/games/{}", BASE_URL, i)).expect("valid URL"), RequestInfo {
+                timestamp: now,
+                data: RawValue::from_string("null".to_owned()).expect("valid JSON").into(),
+                embedded: false
+            })?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn rate_limit() -> Result<()> {
+        let clock = FakeClock::new();
+        let client = client(&clock);
+        fill_cache(&client, RATE_LIMIT_NUM_REQUESTS - 1)?;
+        assert_eq!(client.cache.read().expect("cache lock poisoned").rate_limited(1)?, None);
+        fill_cache(&client, 1)?;
+        assert_eq!(client.cache.read().expect("cache lock poisoned").rate_limited(1)?, Some(RATE_LIMIT_INTERVAL));
+        clock.advance(RATE_LIMIT_INTERVAL / 2);
+        assert_eq!(client.cache.read().expect("cache lock poisoned").rate_limited(1)?, Some(RATE_LIMIT_INTERVAL / 2));
+        clock.advance(RATE_LIMIT_INTERVAL / 2);
+        assert_eq!(client.cache.read().expect("cache lock poisoned").rate_limited(1)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn cache_expiry() -> Result<()> {
+        let clock = FakeClock::new();
+        let client = Builder::new("srcomapi-tests").clock(clock.clone()).cache_timeout(Duration::from_secs(10)).build()?;
+        fill_cache(&client, 1)?;
+        let url = Url::parse(&format!("{}/games/0", BASE_URL)).expect("valid URL");
+        clock.advance(Duration::from_secs(9));
+        assert!(client.cache.read().expect("cache lock poisoned").get(&url).is_some());
+        clock.advance(Duration::from_secs(1));
+        assert!(client.cache.read().expect("cache lock poisoned").get(&url).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn reservations() -> Result<()> {
+        let clock = FakeClock::new();
+        let client = client(&clock);
+        let reservation = client.try_reserve_requests(RATE_LIMIT_NUM_REQUESTS - 1)?.expect("rate limit budget is unused");
+        assert_eq!(reservation.remaining(), RATE_LIMIT_NUM_REQUESTS - 1);
+        assert!(client.try_reserve_requests(2)?.is_none());
+        let single = client.try_reserve_requests(1)?.expect("one request is left");
+        drop(single);
+        drop(reservation);
+        assert_eq!(client.cache.read().expect("cache lock poisoned").reserved, 0);
+        // waiting for the rate limit sleeps on the client's clock
+        fill_cache(&client, RATE_LIMIT_NUM_REQUESTS)?;
+        let start = clock.now();
+        let reservation = client.reserve_requests(RATE_LIMIT_NUM_REQUESTS)?;
+        assert_eq!(clock.now().duration_since(start).expect("clock went backwards"), RATE_LIMIT_INTERVAL);
+        assert_eq!(reservation.remaining(), RATE_LIMIT_NUM_REQUESTS);
+        Ok(())
+    }
+}
@@ -0,0 +1,189 @@
+//! Support for the API's [embedding](https://github.com/speedruncomorg/api/blob/master/version1/embedding.md) feature, which includes related resources in a response to save requests.
+//!
+//! Embedded resources are not exposed directly. Instead, they are moved into the client's cache, so that the methods that would normally request these resources don't need to make a request.
+
+use {
+    std::{
+        collections::BTreeSet,
+        iter::FromIterator
+    },
+    itertools::Itertools,
+    serde_json::{
+        Map,
+        Value
+    },
+    crate::util::path_segment
+};
+
+/// A set of resources to embed into a response, used with the `*_with_embeds` methods and `PaginatedList::set_embeds`.
+///
+/// Which embeds are available depends on the resource being requested, see [the API docs](https://github.com/speedruncomorg/api/blob/master/version1/embedding.md) for details. Nested embeds are separated with a `.`, e.g. `"categories.variables"`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Embeds(BTreeSet<String>);
+
+impl Embeds {
+    /// Adds the given resource to this set of embeds.
+    pub fn embed(mut self, embed: impl ToString) -> Embeds {
+        self.0.insert(embed.to_string());
+        self
+    }
+
+    /// Returns `true` if no resources are embedded.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn query(&self) -> Option<(String, String)> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(("embed".to_owned(), self.0.iter().join(",")))
+        }
+    }
+}
+
+impl<T: ToString> FromIterator<T> for Embeds {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Embeds {
+        Embeds(iter.into_iter().map(|embed| embed.to_string()).collect())
+    }
+}
+
+/// Returns the kind of resource returned by the endpoint with the given path (relative to the API base URL), as the name of the endpoint listing resources of that kind.
+pub(crate) fn resource_kind(path: &str) -> Option<&str> {
+    let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+    let first = segments.next()?;
+    match segments.next_back().unwrap_or(first) {
+        "records" => Some("leaderboards"),
+        "derived-games" | "romhacks" => Some("games"),
+        last @ ("categories" | "developers" | "engines" | "games" | "gametypes" | "genres" | "guests" | "leaderboards" | "levels" | "personal-bests" | "platforms" | "publishers" | "regions" | "runs" | "series" | "users" | "variables") => Some(last),
+        _ => Some(first)
+    }
+}
+
+/// Removes all embedded resources from the given response data, which must be a resource (or list of resources) of the given kind, and returns them along with their API paths.
+///
+/// Each embedded resource is replaced with what the field contains when not embedding, so the response can be deserialized as usual.
+pub(crate) fn extract(kind: &str, data: &mut Value) -> Vec<(String, Value)> {
+    let mut found = Vec::default();
+    match data {
+        Value::Array(items) => for item in items {
+            extract_resource(kind, item, &mut found);
+        },
+        item => extract_resource(kind, item, &mut found)
+    }
+    found
+}
+
+fn unwrap_embedded(value: &mut Value) -> Option<Value> {
+    match value {
+        Value::Object(obj) if obj.len() == 1 && obj.contains_key("data") => obj.remove("data"),
+        _ => None
+    }
+}
+
+fn item_path(kind: &str, item: &Value) -> Option<String> {
+    Some(match (kind, item.get("rel").and_then(Value::as_str)) {
+        ("players", Some("guest")) => format!("/guests/{}", path_segment(item.get("name")?.as_str()?)),
+        ("players", _) | ("moderators", _) => format!("/users/{}", item.get("id")?.as_str()?),
+        (_, _) => format!("/{}/{}", kind, item.get("id")?.as_str()?)
+    })
+}
+
+fn extract_resource(kind: &str, resource: &mut Value, found: &mut Vec<(String, Value)>) {
+    if kind == "personal-bests" {
+        if let Some(run) = resource.get_mut("run") {
+            extract_resource("runs", run, found);
+        }
+        return;
+    }
+    let resource_id = resource.get("id").and_then(Value::as_str).map(str::to_owned);
+    let obj = match resource {
+        Value::Object(obj) => obj,
+        _ => return
+    };
+    let fields = obj.keys().cloned().collect::<Vec<_>>();
+    for field in fields {
+        let embedded = match unwrap_embedded(&mut obj[&field]) {
+            Some(embedded) => embedded,
+            None => continue
+        };
+        let replacement = match embedded {
+            Value::Array(mut items) => {
+                if matches!(&field[..], "game" | "category" | "level" | "platform" | "region") {
+                    // a null resource is embedded as an empty list
+                    obj.insert(field, Value::Null);
+                    continue;
+                }
+                let item_kind = match &field[..] {
+                    "players" | "moderators" => "users",
+                    item_kind => item_kind
+                };
+                for item in &mut items {
+                    extract_resource(item_kind, item, found);
+                    if let Some(path) = item_path(&field, item) {
+                        let mut resource = item.clone();
+                        if let Value::Object(ref mut resource) = resource {
+                            // embedded players have a `rel` field which isn't part of the user or guest resource, but it's still needed for `runner_ref`
+                            resource.remove("rel");
+                        }
+                        found.push((path, envelope(resource)));
+                    }
+                }
+                let replacement = match (kind, &field[..]) {
+                    // these fields are only present when embedding
+                    (_, "moderators") | ("leaderboards", "players") => None,
+                    (_, "players") => Some(Value::Array(items.iter().map(runner_ref).collect())),
+                    (_, _) => Some(Value::Array(items.iter().filter_map(|item| item.get("id").cloned()).collect()))
+                };
+                if let (Some(resource_id), "categories" | "levels" | "variables") = (&resource_id, &field[..]) {
+                    found.push((format!("/{}/{}/{}", kind, resource_id, field), envelope(Value::Array(items))));
+                }
+                replacement
+            }
+            mut item => {
+                let item_kind = match &field[..] {
+                    "game" => "games",
+                    "category" => "categories",
+                    "level" => "levels",
+                    "platform" => "platforms",
+                    "region" => "regions",
+                    _ => {
+                        obj.insert(field, envelope(item));
+                        continue;
+                    }
+                };
+                extract_resource(item_kind, &mut item, found);
+                let id = item.get("id").cloned();
+                if let Some(path) = item_path(item_kind, &item) {
+                    found.push((path, envelope(item)));
+                }
+                id
+            }
+        };
+        match replacement {
+            Some(replacement) => { obj.insert(field, replacement); }
+            None => { obj.remove(&field); }
+        }
+    }
+}
+
+fn envelope(data: Value) -> Value {
+    let mut obj = Map::default();
+    obj.insert("data".to_owned(), data);
+    Value::Object(obj)
+}
+
+fn runner_ref(player: &Value) -> Value {
+    let mut obj = Map::default();
+    match player.get("rel").and_then(Value::as_str) {
+        Some("guest") => {
+            obj.insert("rel".to_owned(), Value::from("guest"));
+            obj.insert("name".to_owned(), player.get("name").cloned().unwrap_or(Value::Null));
+        }
+        _ => {
+            obj.insert("rel".to_owned(), Value::from("user"));
+            obj.insert("id".to_owned(), player.get("id").cloned().unwrap_or(Value::Null));
+        }
+    }
+    Value::Object(obj)
+}
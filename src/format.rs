@@ -0,0 +1,80 @@
+//! Formatting run times for display and for the API
+
+use {
+    std::time::Duration,
+    bigdecimal::{
+        BigDecimal,
+        ToPrimitive,
+        Zero
+    },
+    lazy_static::lazy_static,
+    regex::Regex
+};
+
+lazy_static! {
+    static ref DURATION_RE: Regex = Regex::new("^PT(?:([0-9.]+)H)?(?:([0-9.]+)M)?(?:([0-9.]+)S)?$").unwrap();
+}
+
+/// Options for `format_duration`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Whether to include milliseconds, as in `1:23:45.678`. Otherwise, the duration is truncated to whole seconds. Defaults to `true`.
+    pub show_milliseconds: bool,
+    /// Whether to include the hours even if they're zero, as in `0:12:34`. Defaults to `false`.
+    pub always_show_hours: bool
+}
+
+impl Default for FormatOptions {
+    fn default() -> FormatOptions {
+        FormatOptions {
+            show_milliseconds: true,
+            always_show_hours: false
+        }
+    }
+}
+
+/// Formats a duration like `1:23:45.678`, as commonly used for run times.
+///
+/// Minutes and seconds are zero-padded to two digits, except for the leading component. Durations shorter than an hour are formatted like `23:45.678` unless `FormatOptions::always_show_hours` is set. Milliseconds are truncated, not rounded.
+pub fn format_duration(duration: Duration, options: FormatOptions) -> String {
+    let secs = duration.as_secs();
+    let (hours, mins, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+    let mut formatted = if hours > 0 || options.always_show_hours {
+        format!("{}:{:02}:{:02}", hours, mins, secs)
+    } else {
+        format!("{}:{:02}", mins, secs)
+    };
+    if options.show_milliseconds {
+        formatted.push_str(&format!(".{:03}", duration.subsec_millis()));
+    }
+    formatted
+}
+
+/// Formats a duration in the ISO 8601 format used by the API, e.g. `PT1H23M45.678S`.
+///
+/// Components which are zero are omitted, except that a zero duration is formatted as `PT0S`.
+pub fn format_iso8601(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, mins, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+    let mut formatted = "PT".to_owned();
+    if hours > 0 { formatted.push_str(&format!("{}H", hours)); }
+    if mins > 0 { formatted.push_str(&format!("{}M", mins)); }
+    if duration.subsec_nanos() > 0 {
+        let nanos = format!("{:09}", duration.subsec_nanos());
+        formatted.push_str(&format!("{}.{}S", secs, nanos.trim_end_matches('0')));
+    } else if secs > 0 || hours == 0 && mins == 0 {
+        formatted.push_str(&format!("{}S", secs));
+    }
+    formatted
+}
+
+/// Parses a duration in the ISO 8601 format used by the API, e.g. `PT1H23M45.678S`. Returns `None` if the string is not in this format.
+///
+/// Only hours, minutes, and seconds are supported, since these are the only components used by the API. Each of them may be fractional.
+pub fn parse_iso8601(duration_string: &str) -> Option<Duration> {
+    let captures = DURATION_RE.captures(duration_string)?;
+    let component = |idx| captures.get(idx).map(|component_match| component_match.as_str().parse::<BigDecimal>().ok()).unwrap_or_else(|| Some(BigDecimal::zero()));
+    let total_secs = (component(1)? * BigDecimal::from(60) + component(2)?) * BigDecimal::from(60) + component(3)?;
+    let nanos = (&total_secs % BigDecimal::from(1)) * BigDecimal::from(1_000_000_000);
+    Some(Duration::new(total_secs.to_u64()?, nanos.to_u32()?))
+}
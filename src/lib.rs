@@ -10,22 +10,145 @@ use {
     derive_more::From
 };
 
+pub use crate::format::{
+    FormatOptions,
+    format_duration
+};
+
+pub mod archive;
 pub mod client;
+pub mod embed;
+pub mod format;
 pub mod model;
 pub mod paginated;
+#[cfg(feature = "splits")] pub mod splits;
+#[cfg(feature = "sync")] pub mod sync;
 pub(crate) mod util;
+#[cfg(feature = "vcr")] pub(crate) mod vcr;
+pub mod watch;
 
 /// An enum that contains all the different kinds of errors that can occur in the library.
 #[derive(Debug, From)]
 #[allow(missing_docs)]
 pub enum Error {
+    /// Wraps an error which occurred during a higher-level operation, like requesting a leaderboard, to name that operation. Use `Error::operations` to list all operations.
+    #[from(ignore)]
+    Context {
+        operation: &'static str,
+        source: Box<Error>
+    },
+    #[cfg(feature = "csv")]
+    Csv(csv::Error),
     InvalidHeaderValue(reqwest::header::InvalidHeaderValue),
+    /// Returned if the API responded with an error status. `body` contains the start of the response body, which usually includes an error message from the API.
+    #[from(ignore)]
+    HttpStatus {
+        status: reqwest::StatusCode,
+        body: String
+    },
     Io(io::Error),
     /// Returned by `Category::game` if the API didn't return a link with `"rel": "game"`.
     MissingGameRel,
+    /// Returned by `Game::from_id` if the given string is not a game ID, e.g. because it's an abbreviation.
+    #[from(ignore)]
+    NotAGameId(String),
+    /// Returned by `PaginatedList` when iterating past `paginated::MAX_OFFSET` items, since the API refuses larger offsets.
+    ///
+    /// To access more items, narrow down the list using filters, e.g. by category or status for runs, so that each list has fewer items. Alternatively, `PaginatedList::continue_in_reverse` can be used to reach up to twice as many items.
+    #[from(ignore)]
+    PaginationLimitReached,
     Reqwest(reqwest::Error),
+    /// Wraps an error which occurred while requesting or parsing the response from the given URL.
+    #[from(ignore)]
+    Request {
+        url: reqwest::Url,
+        source: Box<Error>
+    },
     SerDe(serde_json::Error),
-    SystemTime(SystemTimeError)
+    #[cfg(feature = "sync")]
+    Sled(sled::Error),
+    SystemTime(SystemTimeError),
+    /// Returned by a client built using `Builder::replay` if the cassette has no response for the requested URL, or all of them have already been replayed.
+    #[cfg(feature = "vcr")]
+    #[from(ignore)]
+    UnexpectedRequest(reqwest::Url),
+    /// Returned by `Run::fetch_splits` if the splits are not hosted on splits.io.
+    #[cfg(feature = "splits")]
+    #[from(ignore)]
+    UnsupportedSplitsUri(reqwest::Url),
+    /// Returned by `Filter::from_labels` if the variable has no value with the given label.
+    #[from(ignore)]
+    UnknownValueLabel {
+        variable: String,
+        label: String
+    },
+    /// Returned by `Filter::from_labels` if the category has no variable with the given name.
+    #[from(ignore)]
+    UnknownVariableName(String),
+    /// Returned by `FilterBuilder::set` and `Filter::validate` if the value is not one of the variable's possible values.
+    #[from(ignore)]
+    ValueNotInVariable {
+        variable: String,
+        value: String
+    },
+    /// Returned by `Filter::validate` if the variable does not apply to the category.
+    #[from(ignore)]
+    VariableNotInCategory {
+        variable: String,
+        category: String
+    }
+}
+
+impl Error {
+    /// Returns the underlying error, skipping any `Context` and `Request` wrappers.
+    pub fn innermost(&self) -> &Error {
+        match self {
+            Error::Context { source, .. } | Error::Request { source, .. } => source.innermost(),
+            e => e
+        }
+    }
+
+    /// Returns the HTTP status code of the response if the request failed with an error status, e.g. 404 if the requested resource doesn't exist.
+    pub fn http_status(&self) -> Option<reqwest::StatusCode> {
+        match self.innermost() {
+            Error::HttpStatus { status, .. } => Some(*status),
+            Error::Reqwest(e) => e.status(),
+            _ => None
+        }
+    }
+
+    /// Returns the start of the response body if the request failed with an error status.
+    pub fn response_body(&self) -> Option<&str> {
+        match self.innermost() {
+            Error::HttpStatus { body, .. } => Some(body),
+            _ => None
+        }
+    }
+
+    /// Returns the high-level operations during which this error occurred, outermost first.
+    pub fn operations(&self) -> Vec<&'static str> {
+        let mut operations = Vec::default();
+        let mut error = self;
+        loop {
+            match error {
+                Error::Context { operation, source } => {
+                    operations.push(*operation);
+                    error = source;
+                }
+                Error::Request { source, .. } => { error = source; }
+                _ => break operations
+            }
+        }
+    }
+
+    /// Returns the URL of the request which failed, if this error occurred while making an API request or downloading an asset.
+    pub fn request_url(&self) -> Option<&reqwest::Url> {
+        match self {
+            Error::Context { source, .. } => source.request_url(),
+            Error::Request { url, .. } => Some(url),
+            _ => None
+        }
+    }
 }
 
 /// The library's result type.
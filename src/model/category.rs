@@ -5,61 +5,127 @@ use {
         fmt,
         iter::FromIterator
     },
+    chrono::prelude::*,
     itertools::Itertools,
-    serde::Deserialize,
+    reqwest::Url,
+    serde::{
+        Deserialize,
+        Serialize
+    },
     crate::{
         Error,
         Result,
         client::{
             AnnotatedData,
             Client,
-            Link
+            Link,
+            Linked,
+            Resource
         },
+        embed::Embeds,
         model::{
             game::Game,
+            leaderboard::{
+                Leaderboard,
+                LeaderboardOptions
+            },
             run::{
+                PlayerRef,
                 Run,
-                RunData
+                RunsQuery,
+                StatusFilter
             },
+            user::User,
             variable::{
                 Filter,
                 Variable
             }
+        },
+        util::{
+            ResultExt,
+            eq_by_key,
+            extensible
         }
     }
 };
 
-#[derive(Debug, Deserialize, Clone)]
-pub(crate) struct Leaderboard {
-    pub(crate) runs: Vec<LeaderboardEntry>
+/// Whether a category is for full-game runs or IL (individual level) runs. Returned by `Category::category_type`.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum CategoryType {
+    /// A full-game category.
+    PerGame,
+    /// An IL category, which has a separate leaderboard for each level.
+    PerLevel
 }
 
-#[derive(Debug, Deserialize, Clone)]
-pub(crate) struct LeaderboardEntry {
-    pub(crate) place: usize,
-    pub(crate) run: RunData
+/// Whether the number in `Players` is exact or a maximum.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PlayersType {
+    /// Runs must have exactly the given number of players.
+    Exactly,
+    /// Runs can have any number of players up to the given number.
+    UpTo
 }
 
-#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
-#[serde(rename_all = "kebab-case")]
-enum CategoryType {
-    PerGame,
-    PerLevel
+/// How many players participate in runs of a category.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct Players {
+    /// Whether `value` is exact or a maximum.
+    #[serde(rename = "type")]
+    pub players_type: PlayersType,
+    /// The number of players.
+    pub value: u16
+}
+
+impl Players {
+    /// Returns `true` if a run with the given number of players is allowed in the category.
+    pub fn allows(&self, num_players: usize) -> bool {
+        match self.players_type {
+            PlayersType::Exactly => num_players == usize::from(self.value),
+            PlayersType::UpTo => (1..=usize::from(self.value)).contains(&num_players)
+        }
+    }
 }
 
 /// The cached data for a category. This type is an implementation detail. You're probably looking for `Category` instead.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(remote = "Self")]
 pub struct CategoryData {
     id: String,
     links: Vec<Link>,
+    miscellaneous: bool,
     name: String,
+    players: Players,
+    rules: Option<String>,
     #[serde(rename = "type")]
-    cat_type: CategoryType
+    cat_type: CategoryType,
+    #[serde(with = "url_serde")]
+    weblink: Url,
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extra")]
+    extra: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip)]
+    raw: Option<std::sync::Arc<serde_json::Value>>
 }
 
 /// Categories are the different rulesets for speedruns.
 pub type Category = AnnotatedData<CategoryData>;
 
+impl Resource for CategoryData {
+    fn path(&self) -> String {
+        format!("/categories/{}", self.id)
+    }
+}
+
+impl Linked for CategoryData {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+extensible!(CategoryData);
+
 impl Category {
     /// Returns the category with the given ID.
     pub fn from_id(client: &Client, id: impl fmt::Display) -> Result<Category> {
@@ -68,13 +134,37 @@ impl Category {
         ))
     }
 
+    /// Returns the category with the given ID, requesting the given resources to be embedded in the response.
+    ///
+    /// See the `embed` module for details.
+    pub fn from_id_with_embeds(client: &Client, id: impl fmt::Display, embeds: &Embeds) -> Result<Category> {
+        Ok(client.annotate(
+            client.get_query(format!("/categories/{}", id), embeds.query())?
+        ))
+    }
+
+    /// Returns all verified runs of this category which match the given filter, including obsoleted runs.
+    ///
+    /// Unlike the leaderboard, which only lists each runner's best run, this can be used to analyze a category's full history. The filter is applied client-side, since the `/runs` endpoint doesn't support filtering by variables, so all of the category's verified runs are requested.
+    pub fn all_runs(&self, filter: &Filter) -> impl Iterator<Item = Result<Run>> {
+        let filter = filter.clone();
+        Run::search(self.client.clone(), RunsQuery::default().category(self.id()).status(StatusFilter::Verified))
+            .filter(move |run| run.as_ref().map_or(true, |run| filter.matches(run)))
+    }
+
+    /// Returns whether this is a full-game or IL category.
+    pub fn category_type(&self) -> CategoryType {
+        self.data.cat_type
+    }
+
     /// Returns the game to which this category belongs.
     pub fn game(&self) -> Result<Game> {
+        const OPERATION: &str = "Category::game";
         let (link,) = self.data.links.iter()
-            .filter(|link| link.rel.as_ref().map_or(false, |rel| rel == "game"))
-            .collect_tuple().ok_or(Error::MissingGameRel)?;
+            .filter(|link| link.rel.as_ref().is_some_and(|rel| rel == "game"))
+            .collect_tuple().ok_or(Error::MissingGameRel).context(OPERATION)?;
         Ok(self.client.annotate(
-            self.client.get_abs(link.uri.clone())?
+            self.client.get_abs(link.uri.clone()).context(OPERATION)?
         ))
     }
 
@@ -88,10 +178,63 @@ impl Category {
         self.data.cat_type == CategoryType::PerLevel
     }
 
+    /// Returns `true` if this category is miscellaneous, i.e. hidden behind a “more” button by default on speedrun.com.
+    pub fn is_misc(&self) -> bool {
+        self.data.miscellaneous
+    }
+
+    /// Returns how many players participate in runs of this category.
+    pub fn players(&self) -> Players {
+        self.data.players
+    }
+
+    /// Returns the rules of this category, if any.
+    pub fn rules(&self) -> Option<&str> {
+        self.data.rules.as_deref()
+    }
+
+    /// Returns the leaderboards for all combinations of this category's subcategories, like they're displayed on speedrun.com.
+    ///
+    /// Each leaderboard is paired with the labels of the subcategory values it's filtered by, in the order the variables are returned by the API. The values of each variable are combined in order of their labels. If the category has no subcategories, a single unfiltered leaderboard with no labels is returned. Subcategory variables without any values are ignored.
+    ///
+    /// The variables are requested immediately, while the leaderboards are requested lazily as the iterator is advanced, so the client's rate limit applies as usual.
+    ///
+    /// # Errors
+    ///
+    /// The leaderboards will error if this is an IL category.
+    pub fn subcategory_leaderboards(&self) -> Result<impl Iterator<Item = Result<(Vec<String>, Leaderboard)>> + '_> {
+        const OPERATION: &str = "Category::subcategory_leaderboards";
+        let subcategories = self.variables::<Vec<_>>().context(OPERATION)?.into_iter()
+            .filter(|variable| variable.is_subcategory() && variable.scope().applies_to_full_game())
+            .map(|variable| variable.values().into_iter()
+                .map(|value| (variable.id().to_owned(), value.id().to_owned(), value.label().to_owned()))
+                .sorted_by(|(_, id1, label1), (_, id2, label2)| label1.cmp(label2).then_with(|| id1.cmp(id2)))
+                .collect::<Vec<_>>()
+            )
+            // a variable without values would make the product empty
+            .filter(|values| !values.is_empty())
+            .collect::<Vec<_>>();
+        let combinations = if subcategories.is_empty() {
+            vec![Vec::default()]
+        } else {
+            subcategories.into_iter().multi_cartesian_product().collect()
+        };
+        Ok(combinations.into_iter().map(move |combination| {
+            let filter = combination.iter().map(|(variable_id, value_id, _)| (variable_id, value_id)).collect::<Filter>();
+            let labels = combination.into_iter().map(|(_, _, label)| label).collect();
+            Ok((labels, self.filtered_full_leaderboard(&filter).context(OPERATION)?))
+        }))
+    }
+
     /// Returns all variables applicable to this category.
     pub fn variables<C: FromIterator<Variable>>(&self) -> Result<C> {
         self.client.get_annotated_collection(format!("/categories/{}/variables", self.id()))
     }
+
+    /// Returns the link to this category's leaderboard on speedrun.com.
+    pub fn weblink(&self) -> &Url {
+        &self.data.weblink
+    }
 }
 
 /// This trait is implemented on types for which leaderboards are available, namely:
@@ -101,20 +244,67 @@ impl Category {
 ///
 /// It provides methods to access these leaderboards.
 pub trait ToLeaderboard: Sized {
+    /// Returns a leaderboard for this category, filtered by the given variable/value pairs and additional options, including the places of the runs and other metadata.
+    fn leaderboard_with_options(self, filter: &Filter, options: &LeaderboardOptions) -> Result<Leaderboard>;
+
+    /// Returns a leaderboard for this category, filtered by the given variable/value pairs, including the places of the runs and other metadata.
+    ///
+    /// The players of the runs are embedded in the response, so calling `Run::runners` on the runs does not make additional API requests as long as the response is cached.
+    fn filtered_full_leaderboard(self, filter: &Filter) -> Result<Leaderboard> {
+        self.leaderboard_with_options(filter, &LeaderboardOptions::default())
+    }
+
     /// Returns a leaderboard for this category, filtered by the given variable/value pairs.
-    fn filtered_leaderboard<C: FromIterator<Run>>(self, filter: &Filter) -> Result<C>;
+    fn filtered_leaderboard<C: FromIterator<Run>>(self, filter: &Filter) -> Result<C> {
+        Ok(self.filtered_full_leaderboard(filter)?.runs())
+    }
 
     /// A convenience method returning the first place from a filtered version of this category's leaderboard.
-    fn filtered_wr(self, filter: &Filter) -> Result<Option<Run>>;
+    ///
+    /// If the world record is tied, this method returns whichever run the API lists first.
+    ///
+    /// If no run has been verified for the given filter, `Ok(None)` is returned.
+    fn filtered_wr(self, filter: &Filter) -> Result<Option<Run>> {
+        let lb = self.filtered_full_leaderboard(filter)?;
+        Ok(lb.data.runs.first().map(|entry| lb.client.annotate(entry.run.clone())))
+    }
 
     /// Returns true if the world record for this category and the given filter is tied.
-    fn filtered_wr_is_tied(self, filter: &Filter) -> Result<bool>;
+    fn filtered_wr_is_tied(self, filter: &Filter) -> Result<bool> {
+        let lb = self.filtered_full_leaderboard(filter)?;
+        Ok(lb.data.runs.len() > 1 && lb.data.runs[1].place == 1)
+    }
+
+    /// Returns the leaderboard for this category, including the places of the runs and other metadata.
+    fn full_leaderboard(self) -> Result<Leaderboard> {
+        self.filtered_full_leaderboard(&Filter::default())
+    }
+
+    /// Returns a leaderboard for this category, filtered by the given variable/value pairs, as it was on the given date.
+    ///
+    /// This is a shorthand for `leaderboard_with_options` with `LeaderboardOptions::date`.
+    fn leaderboard_at(self, date: NaiveDate, filter: &Filter) -> Result<Leaderboard> {
+        self.leaderboard_with_options(filter, &LeaderboardOptions::default().date(date))
+    }
 
     /// Returns the leaderboard for this category, i.e. all non-obsoleted runs.
     fn leaderboard<C: FromIterator<Run>>(self) -> Result<C> {
         self.filtered_leaderboard(&Filter::default())
     }
 
+    /// Returns the given user's place and run on a filtered version of this category's leaderboard.
+    ///
+    /// Runs with multiple players are found if the user is any of them. The players are matched by their API IDs, so no additional requests are made for the other runners.
+    ///
+    /// If the user has no run on the leaderboard, `Ok(None)` is returned.
+    fn place_of(self, user: &User, filter: &Filter) -> Result<Option<(usize, Run)>> {
+        const OPERATION: &str = "ToLeaderboard::place_of";
+        let lb = self.filtered_full_leaderboard(filter).context(OPERATION)?;
+        Ok(lb.entries::<Vec<_>>().into_iter()
+            .find(|entry| entry.run.player_refs().any(|player| player == PlayerRef::User(user.id())))
+            .map(|entry| (entry.place, entry.run)))
+    }
+
     /// A convenience method returning the first place from this category's leaderboard, i.e. the current world record of the category.
     ///
     /// If the world record is tied, this method returns whichever run the API lists first.
@@ -137,38 +327,19 @@ impl fmt::Display for Category {
     }
 }
 
+eq_by_key!(Category, id);
+
 impl ToLeaderboard for &Category {
-    /// Returns a leaderboard for this full-game category, filtered by the given variable/value pairs.
+    /// Returns a leaderboard for this full-game category, filtered by the given variable/value pairs and additional options, including the places of the runs and other metadata.
     ///
     /// # Errors
     ///
     /// Will error if this is an IL category.
-    fn filtered_leaderboard<C: FromIterator<Run>>(self, filter: &Filter) -> Result<C> {
-        Ok(
-            self.client.get_query::<_, _, _, _, Leaderboard>(format!("/leaderboards/{}/category/{}", self.game()?.id(), self.id()), filter)?
-                .runs
-                .into_iter()
-                .map(|entry| self.client.annotate(entry.run))
-                .collect()
-        )
-    }
-
-    /// A convenience method returning the first place from a filtered version of this category's leaderboard.
-    ///
-    /// If the world record is tied, this method returns whichever run the API lists first.
-    ///
-    /// If no run has been verified for the given filter, `Ok(None)` is returned.
-    fn filtered_wr(self, filter: &Filter) -> Result<Option<Run>> {
-        let mut lb = self.client.get_query::<_, _, _, _, Leaderboard>(format!("/leaderboards/{}/category/{}", self.game()?.id(), self.id()), filter)?
-            .runs;
-        if lb.is_empty() { return Ok(None); }
-        Ok(Some(self.client.annotate(lb.remove(0).run)))
-    }
-
-    /// Returns true if the world record for this category and the given filter is tied.
-    fn filtered_wr_is_tied(self, filter: &Filter) -> Result<bool> {
-        let lb = self.client.get_query::<_, _, _, _, Leaderboard>(format!("/leaderboards/{}/category/{}", self.game()?.id(), self.id()), filter)?
-            .runs;
-        Ok(lb.len() > 1 && lb[1].place == 1)
+    fn leaderboard_with_options(self, filter: &Filter, options: &LeaderboardOptions) -> Result<Leaderboard> {
+        const OPERATION: &str = "Category::leaderboard_with_options";
+        if options.validate { filter.validate(self).context(OPERATION)?; }
+        Ok(self.client.annotate(
+            self.client.get_query(format!("/leaderboards/{}/category/{}", self.game().context(OPERATION)?.id(), self.id()), options.query(filter)).context(OPERATION)?
+        ))
     }
 }
@@ -0,0 +1,83 @@
+//! Developers are the companies or individuals who developed a game
+
+use {
+    std::fmt,
+    serde::{
+        Deserialize,
+        Serialize
+    },
+    crate::{
+        Result,
+        client::{
+            AnnotatedData,
+            Client,
+            Link,
+            Linked,
+            Resource
+        },
+        paginated::PaginatedList,
+        util::{
+            eq_by_key,
+            extensible
+        }
+    }
+};
+
+/// The cached data for a developer. This type is an implementation detail. You're probably looking for `Developer` instead.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(remote = "Self")]
+pub struct DeveloperData {
+    id: String,
+    #[serde(default)]
+    links: Vec<Link>,
+    name: String,
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extra")]
+    extra: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip)]
+    raw: Option<std::sync::Arc<serde_json::Value>>
+}
+
+/// Developers are the companies or individuals who developed a game.
+pub type Developer = AnnotatedData<DeveloperData>;
+
+impl Resource for DeveloperData {
+    fn path(&self) -> String {
+        format!("/developers/{}", self.id)
+    }
+}
+
+impl Linked for DeveloperData {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+extensible!(DeveloperData);
+
+impl Developer {
+    /// Returns a paginated list of all developers on speedrun.com.
+    pub fn list(client: impl Into<Client>) -> PaginatedList<DeveloperData> {
+        PaginatedList::new(client.into(), "/developers".into())
+    }
+
+    /// Returns the developer with the given ID.
+    pub fn from_id(client: &Client, id: impl fmt::Display) -> Result<Developer> {
+        Ok(client.annotate(
+            client.get(format!("/developers/{}", id))?
+        ))
+    }
+
+    /// Returns this developer's API ID.
+    pub fn id(&self) -> &str {
+        &self.data.id
+    }
+}
+
+/// Displays the developer name.
+impl fmt::Display for Developer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.data.name.fmt(f)
+    }
+}
+
+eq_by_key!(Developer, id);
@@ -0,0 +1,83 @@
+//! Engines are the game engines a game is built with
+
+use {
+    std::fmt,
+    serde::{
+        Deserialize,
+        Serialize
+    },
+    crate::{
+        Result,
+        client::{
+            AnnotatedData,
+            Client,
+            Link,
+            Linked,
+            Resource
+        },
+        paginated::PaginatedList,
+        util::{
+            eq_by_key,
+            extensible
+        }
+    }
+};
+
+/// The cached data for an engine. This type is an implementation detail. You're probably looking for `Engine` instead.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(remote = "Self")]
+pub struct EngineData {
+    id: String,
+    #[serde(default)]
+    links: Vec<Link>,
+    name: String,
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extra")]
+    extra: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip)]
+    raw: Option<std::sync::Arc<serde_json::Value>>
+}
+
+/// Engines are the game engines a game is built with.
+pub type Engine = AnnotatedData<EngineData>;
+
+impl Resource for EngineData {
+    fn path(&self) -> String {
+        format!("/engines/{}", self.id)
+    }
+}
+
+impl Linked for EngineData {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+extensible!(EngineData);
+
+impl Engine {
+    /// Returns a paginated list of all engines on speedrun.com.
+    pub fn list(client: impl Into<Client>) -> PaginatedList<EngineData> {
+        PaginatedList::new(client.into(), "/engines".into())
+    }
+
+    /// Returns the engine with the given ID.
+    pub fn from_id(client: &Client, id: impl fmt::Display) -> Result<Engine> {
+        Ok(client.annotate(
+            client.get(format!("/engines/{}", id))?
+        ))
+    }
+
+    /// Returns this engine's API ID.
+    pub fn id(&self) -> &str {
+        &self.data.id
+    }
+}
+
+/// Displays the engine name.
+impl fmt::Display for Engine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.data.name.fmt(f)
+    }
+}
+
+eq_by_key!(Engine, id);
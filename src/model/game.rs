@@ -2,29 +2,97 @@
 
 use {
     std::{
+        collections::BTreeMap,
         fmt,
+        io,
         iter::FromIterator
     },
+    chrono::prelude::*,
     reqwest::Url,
-    serde::Deserialize,
+    serde::{
+        Deserialize,
+        Serialize
+    },
     crate::{
+        Error,
         Result,
         client::{
             AnnotatedData,
-            Client
+            Client,
+            Link,
+            Linked,
+            NamePreference,
+            Resource
         },
+        embed::Embeds,
         model::{
             category::Category,
-            level::Level
+            developer::Developer,
+            engine::Engine,
+            gametype::Gametype,
+            genre::Genre,
+            level::Level,
+            platform::Platform,
+            publisher::Publisher,
+            region::Region,
+            run::TimingMethod,
+            series::Series,
+            user::User,
+            variable::Variable
+        },
+        paginated::{
+            Direction,
+            PaginatedList,
+            SortKey
         },
-        paginated::PaginatedList
+        util::{
+            eq_by_key,
+            extensible
+        }
     }
 };
 
-pub(crate) static LIST_URL: &'static str = "/games?_bulk=yes";
+pub(crate) static LIST_URL: &str = "/games?_bulk=yes";
+
+/// The API ID of a game, as opposed to its abbreviation. Used by `Game::from_id`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+pub struct GameId(String);
+
+impl GameId {
+    /// Returns the ID as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for GameId {
+    fn from(id: String) -> GameId {
+        GameId(id)
+    }
+}
+
+impl From<&str> for GameId {
+    fn from(id: &str) -> GameId {
+        GameId(id.to_owned())
+    }
+}
+
+impl From<&String> for GameId {
+    fn from(id: &String) -> GameId {
+        GameId(id.clone())
+    }
+}
+
+/// Displays the ID itself.
+impl fmt::Display for GameId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
 
 /// The different names registered for a game.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Names {
     /// The game's international, or main, name.
     pub international: String,
@@ -34,32 +102,396 @@ pub struct Names {
     pub twitch: Option<String>
 }
 
+impl Names {
+    /// Returns the Japanese name if it's preferred and registered, and the international name otherwise.
+    pub fn preferred(&self, preference: NamePreference) -> &str {
+        preference.choose(&self.international, self.japanese.as_deref())
+    }
+}
+
+/// An image associated with a game.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Asset {
+    /// The URL of the image.
+    #[serde(with = "url_serde")]
+    pub uri: Url,
+    /// The width of the image in pixels, if known.
+    pub width: Option<u32>,
+    /// The height of the image in pixels, if known.
+    pub height: Option<u32>
+}
+
+/// The images associated with a game, like cover art and trophies. Any of them may be missing.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct Assets {
+    /// The game's logo.
+    pub logo: Option<Asset>,
+    /// The game's cover art in the smallest size.
+    pub cover_tiny: Option<Asset>,
+    /// The game's cover art in small size.
+    pub cover_small: Option<Asset>,
+    /// The game's cover art in medium size.
+    pub cover_medium: Option<Asset>,
+    /// The game's cover art in the largest size.
+    pub cover_large: Option<Asset>,
+    /// The game's favicon.
+    pub icon: Option<Asset>,
+    /// The trophy displayed next to first-place runs.
+    #[serde(rename = "trophy-1st")]
+    pub trophy_1st: Option<Asset>,
+    /// The trophy displayed next to second-place runs.
+    #[serde(rename = "trophy-2nd")]
+    pub trophy_2nd: Option<Asset>,
+    /// The trophy displayed next to third-place runs.
+    #[serde(rename = "trophy-3rd")]
+    pub trophy_3rd: Option<Asset>,
+    /// The trophy displayed next to fourth-place runs. Most games don't have one.
+    #[serde(rename = "trophy-4th")]
+    pub trophy_4th: Option<Asset>,
+    /// The background image of the game's pages.
+    pub background: Option<Asset>,
+    /// The foreground image of the game's pages.
+    pub foreground: Option<Asset>
+}
+
+impl Assets {
+    /// Returns the trophy for the given leaderboard place, if the game has one.
+    pub fn trophy(&self, place: usize) -> Option<&Asset> {
+        match place {
+            1 => self.trophy_1st.as_ref(),
+            2 => self.trophy_2nd.as_ref(),
+            3 => self.trophy_3rd.as_ref(),
+            4 => self.trophy_4th.as_ref(),
+            _ => None
+        }
+    }
+}
+
+/// The rules that apply to all runs of a game.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct Ruleset {
+    /// Whether run times on the game's leaderboards are displayed with milliseconds.
+    pub show_milliseconds: bool,
+    /// Whether runs need to be verified by a moderator before they appear on the leaderboards.
+    pub require_verification: bool,
+    /// Whether runs need to include a video.
+    pub require_video: bool,
+    /// The timing methods in which runs can be timed.
+    pub run_times: Vec<TimingMethod>,
+    /// The timing method by which runs are ranked by default.
+    pub default_time: TimingMethod,
+    /// Whether runs done on emulator are allowed.
+    pub emulators_allowed: bool
+}
+
+/// The permissions a moderator has for a game, returned by `Game::moderators`.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ModeratorRole {
+    /// A regular moderator, who can verify runs.
+    Moderator,
+    /// A super moderator, who can additionally edit the game and add or remove moderators.
+    SuperModerator,
+    /// A role which is not known to this library, e.g. because it was added to the API after this version was released.
+    #[serde(other)]
+    Unknown
+}
+
+/// The fields by which the results of `Game::search` and other lists of games can be sorted, used with `GamesQuery::order_by` or `PaginatedList::order_by`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderBy {
+    /// Sort by international name. This is the default.
+    NameInternational,
+    /// Sort by Japanese name.
+    NameJapanese,
+    /// Sort by abbreviation.
+    Abbreviation,
+    /// Sort by release year.
+    Released,
+    /// Sort by the date the game was added to speedrun.com.
+    Created,
+    /// Sort by similarity to the `name` search term.
+    Similarity
+}
+
+impl OrderBy {
+    fn as_str(&self) -> &'static str {
+        match self {
+            OrderBy::NameInternational => "name.int",
+            OrderBy::NameJapanese => "name.jap",
+            OrderBy::Abbreviation => "abbreviation",
+            OrderBy::Released => "released",
+            OrderBy::Created => "created",
+            OrderBy::Similarity => "similarity"
+        }
+    }
+}
+
+impl SortKey for OrderBy {
+    type Item = GameData;
+
+    fn param_value(&self) -> &'static str {
+        self.as_str()
+    }
+}
+
+/// Search parameters for `Game::search`.
+///
+/// All filters are optional and can be combined. Filters taking an ID expect the API ID of the respective resource.
+#[derive(Debug, Default, Clone)]
+pub struct GamesQuery {
+    params: Vec<(&'static str, String)>
+}
+
+impl GamesQuery {
+    fn param(mut self, key: &'static str, value: impl ToString) -> GamesQuery {
+        self.params.retain(|&(existing_key, _)| existing_key != key);
+        self.params.push((key, value.to_string()));
+        self
+    }
+
+    /// Only returns games whose name contains the given string (fuzzy search).
+    pub fn name(self, name: impl ToString) -> GamesQuery {
+        self.param("name", name)
+    }
+
+    /// Only returns games with the given abbreviation.
+    pub fn abbreviation(self, abbreviation: impl ToString) -> GamesQuery {
+        self.param("abbreviation", abbreviation)
+    }
+
+    /// Only returns games released in the given year.
+    pub fn released(self, year: u16) -> GamesQuery {
+        self.param("released", year)
+    }
+
+    /// Only returns games with the given gametype.
+    pub fn gametype(self, id: impl ToString) -> GamesQuery {
+        self.param("gametype", id)
+    }
+
+    /// Only returns games that can be run on the given platform.
+    pub fn platform(self, id: impl ToString) -> GamesQuery {
+        self.param("platform", id)
+    }
+
+    /// Only returns games that can be run in the given region.
+    pub fn region(self, id: impl ToString) -> GamesQuery {
+        self.param("region", id)
+    }
+
+    /// Only returns games with the given genre.
+    pub fn genre(self, id: impl ToString) -> GamesQuery {
+        self.param("genre", id)
+    }
+
+    /// Only returns games built with the given engine.
+    pub fn engine(self, id: impl ToString) -> GamesQuery {
+        self.param("engine", id)
+    }
+
+    /// Only returns games developed by the given developer.
+    pub fn developer(self, id: impl ToString) -> GamesQuery {
+        self.param("developer", id)
+    }
+
+    /// Only returns games published by the given publisher.
+    pub fn publisher(self, id: impl ToString) -> GamesQuery {
+        self.param("publisher", id)
+    }
+
+    /// Only returns games moderated by the given user.
+    pub fn moderator(self, id: impl ToString) -> GamesQuery {
+        self.param("moderator", id)
+    }
+
+    /// Configures the sort order of the results.
+    pub fn order_by(self, order_by: OrderBy) -> GamesQuery {
+        self.param("orderby", order_by.as_str())
+    }
+
+    /// Configures the sort direction of the results.
+    pub fn direction(self, direction: Direction) -> GamesQuery {
+        self.param("direction", direction.as_str())
+    }
+}
+
 /// The cached data for a game. This type is an implementation detail. You're probably looking for `Game` instead.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(remote = "Self")]
 pub struct GameData {
     id: String,
     abbreviation: String,
+    #[serde(default)]
+    assets: Option<Assets>,
+    #[serde(default, rename = "boostDistinctDonorsCount")]
+    boost_distinct_donors_count: u32,
+    #[serde(default, rename = "boostReceivedCount")]
+    boost_received_count: u32,
+    #[serde(default)]
+    created: Option<DateTime<Utc>>,
+    #[serde(default)]
+    developers: Vec<String>,
+    #[serde(default)]
+    discord: Option<String>,
+    #[serde(default)]
+    engines: Vec<String>,
+    #[serde(default)]
+    gametypes: Vec<String>,
+    #[serde(default)]
+    genres: Vec<String>,
+    #[serde(default)]
+    links: Vec<Link>,
+    #[serde(default)]
+    moderators: BTreeMap<String, ModeratorRole>,
     names: Names,
+    #[serde(default)]
+    platforms: Vec<String>,
+    #[serde(default)]
+    publishers: Vec<String>,
+    #[serde(default)]
+    regions: Vec<String>,
+    #[serde(default)]
+    released: u16,
+    #[serde(default)]
+    romhack: bool,
+    #[serde(default, rename = "release-date")]
+    release_date: Option<NaiveDate>,
+    #[serde(default)]
+    ruleset: Option<Ruleset>,
     #[serde(with = "url_serde")]
-    weblink: Url
+    weblink: Url,
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extra")]
+    extra: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip)]
+    raw: Option<std::sync::Arc<serde_json::Value>>
 }
 
 /// Games are the things users do speedruns in.
 pub type Game = AnnotatedData<GameData>;
 
+impl Resource for GameData {
+    fn path(&self) -> String {
+        format!("/games/{}", self.id)
+    }
+}
+
+impl Linked for GameData {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+extensible!(GameData);
+
+/// A game along with its categories, levels, and variables, as returned by `Game::from_id_embedded`.
+#[derive(Debug, Clone)]
+pub struct GameBundle {
+    /// The game itself.
+    pub game: Game,
+    /// All speedrun categories defined for the game.
+    pub categories: Vec<Category>,
+    /// All individual levels defined for the game.
+    pub levels: Vec<Level>,
+    /// All variables applicable to any of the game's categories.
+    pub variables: Vec<Variable>
+}
+
 impl Game {
     /// Returns a paginated list of all games on speedrun.com.
-    pub fn list(client: impl Into<Client>) -> PaginatedList<GameData> {
+    ///
+    /// The games are requested in bulk mode, which allows larger pages but only includes each game's ID, names, abbreviation, and weblink. Use `BulkGame::hydrate` to request the full data for a game.
+    pub fn list(client: impl Into<Client>) -> PaginatedList<BulkGameData> {
         let mut list = PaginatedList::new(client.into(), LIST_URL.into());
         list.set_page_size(1000);
         list
     }
 
-    /// Returns the game with the given ID or abbreviation.
-    pub fn from_id(client: &Client, id: impl fmt::Display) -> Result<Game> {
-        Ok(client.annotate(
-            client.get(format!("/games/{}", id))?
-        ))
+    /// Returns a paginated list of all games matching the given search parameters.
+    pub fn search(client: impl Into<Client>, query: GamesQuery) -> PaginatedList<GameData> {
+        PaginatedList::with_query(client.into(), "/games".into(), query.params.into_iter().map(|(key, value)| (key.to_owned(), value)).collect())
+    }
+
+    /// Returns the game with the given API ID.
+    ///
+    /// To look up a game by its abbreviation, use `from_abbreviation` instead.
+    ///
+    /// # Errors
+    ///
+    /// In addition to request errors, returns `Error::NotAGameId` if the API returned a game with a different ID, which happens if `id` is actually an abbreviation.
+    pub fn from_id(client: &Client, id: impl Into<GameId>) -> Result<Game> {
+        let id = id.into();
+        let game = client.annotate::<GameData>(client.get(format!("/games/{}", id))?);
+        if game.id() != id.as_str() { return Err(Error::NotAGameId(id.0)); }
+        Ok(game)
+    }
+
+    /// Returns the game with the given abbreviation, as used in its URL on speedrun.com. Returns `Ok(None)` if there is no such game.
+    ///
+    /// Abbreviations are compared case-insensitively.
+    pub fn from_abbreviation(client: &Client, abbreviation: impl fmt::Display) -> Result<Option<Game>> {
+        let abbreviation = abbreviation.to_string();
+        Game::search(client, GamesQuery::default().abbreviation(&abbreviation))
+            .find(|game| game.as_ref().map_or(true, |game| game.abbreviation().eq_ignore_ascii_case(&abbreviation)))
+            .transpose()
+    }
+
+    /// Returns the game with the given API ID, requesting the given resources to be embedded in the response.
+    ///
+    /// See the `embed` module for details.
+    ///
+    /// # Errors
+    ///
+    /// Like `from_id`, returns `Error::NotAGameId` if `id` is actually an abbreviation.
+    pub fn from_id_with_embeds(client: &Client, id: impl Into<GameId>, embeds: &Embeds) -> Result<Game> {
+        let id = id.into();
+        let game = client.annotate::<GameData>(client.get_query(format!("/games/{}", id), embeds.query())?);
+        if game.id() != id.as_str() { return Err(Error::NotAGameId(id.0)); }
+        Ok(game)
+    }
+
+    /// Returns the game with the given API ID, along with its categories, levels, and variables.
+    ///
+    /// Only a single API request is made, since the categories, levels, and variables are embedded in the response.
+    ///
+    /// # Errors
+    ///
+    /// Like `from_id`, returns `Error::NotAGameId` if `id` is actually an abbreviation.
+    pub fn from_id_embedded(client: &Client, id: impl Into<GameId>) -> Result<GameBundle> {
+        let game = Game::from_id_with_embeds(client, id, &Embeds::default().embed("categories.variables").embed("levels"))?;
+        let categories = game.categories::<Vec<_>>()?;
+        let levels = game.levels()?;
+        let mut variables = Vec::<Variable>::default();
+        for category in &categories {
+            for variable in category.variables::<Vec<_>>()? {
+                if !variables.iter().any(|known| known.id() == variable.id()) {
+                    variables.push(variable);
+                }
+            }
+        }
+        Ok(GameBundle { game, categories, levels, variables })
+    }
+
+    /// Returns this game's abbreviation, as used in its URL on speedrun.com.
+    pub fn abbreviation(&self) -> &str {
+        &self.data.abbreviation
+    }
+
+    /// Returns the images associated with this game, like cover art and trophies.
+    pub fn assets(&self) -> Option<&Assets> {
+        self.data.assets.as_ref()
+    }
+
+    /// Returns how many different users have boosted this game on speedrun.com.
+    pub fn boost_distinct_donors_count(&self) -> u32 {
+        self.data.boost_distinct_donors_count
+    }
+
+    /// Returns how many boosts this game has received on speedrun.com.
+    pub fn boost_received_count(&self) -> u32 {
+        self.data.boost_received_count
     }
 
     /// Returns all speedrun categories defined for the game.
@@ -67,30 +499,244 @@ impl Game {
         self.client.get_annotated_collection(format!("/games/{}/categories", self.id()))
     }
 
+    /// Returns a paginated list of games derived from this one, such as category extensions and ROM hacks.
+    pub fn derived_games(&self) -> PaginatedList<GameData> {
+        PaginatedList::new(self.client.clone(), format!("/games/{}/derived-games", self.id()))
+    }
+
+    /// Returns the timestamp when this game was added to speedrun.com. `None` for games added before this was recorded.
+    pub fn created(&self) -> Option<DateTime<Utc>> {
+        self.data.created
+    }
+
+    /// Returns the API IDs of the developers of this game.
+    pub fn developer_ids(&self) -> &[String] {
+        &self.data.developers
+    }
+
+    /// Returns the developers of this game.
+    pub fn developers<C: FromIterator<Developer>>(&self) -> Result<C> {
+        self.resolve("developers", &self.data.developers, |client, id| Developer::from_id(client, id))
+    }
+
+    /// Returns the invite link to this game's Discord server, if any.
+    pub fn discord(&self) -> Option<&str> {
+        // the API returns an empty string for games without a Discord server
+        self.data.discord.as_deref().filter(|discord| !discord.is_empty())
+    }
+
+    /// Downloads the game's cover art in the largest available size and writes it to the given writer. Returns the number of bytes written, or `Ok(None)` if the game has no cover art.
+    ///
+    /// See `Client::download_asset` for details.
+    pub fn download_cover(&self, writer: impl io::Write) -> Result<Option<u64>> {
+        self.data.assets.as_ref()
+            .and_then(|assets| assets.cover_large.as_ref().or(assets.cover_medium.as_ref()).or(assets.cover_small.as_ref()).or(assets.cover_tiny.as_ref()))
+            .map(|cover| self.client.download_asset(&cover.uri, writer))
+            .transpose()
+    }
+
+    /// Returns the API IDs of the engines this game is built with.
+    pub fn engine_ids(&self) -> &[String] {
+        &self.data.engines
+    }
+
+    /// Returns the engines this game is built with.
+    pub fn engines<C: FromIterator<Engine>>(&self) -> Result<C> {
+        self.resolve("engines", &self.data.engines, |client, id| Engine::from_id(client, id))
+    }
+
+    /// Returns the gametypes this game is classified as, e.g. ROM hack or fangame.
+    pub fn gametypes<C: FromIterator<Gametype>>(&self) -> Result<C> {
+        self.resolve("gametypes", &self.data.gametypes, |client, id| Gametype::from_id(client, id))
+    }
+
+    /// Returns the API IDs of the genres this game belongs to.
+    pub fn genre_ids(&self) -> &[String] {
+        &self.data.genres
+    }
+
+    /// Returns the genres this game belongs to.
+    pub fn genres<C: FromIterator<Genre>>(&self) -> Result<C> {
+        self.resolve("genres", &self.data.genres, |client, id| Genre::from_id(client, id))
+    }
+
     /// Returns this game's API ID.
     pub fn id(&self) -> &str {
         &self.data.id
     }
 
+    /// Returns `true` if this game is a ROM hack of another game.
+    pub fn is_romhack(&self) -> bool {
+        self.data.romhack
+    }
+
     /// Returns all individual levels defined for the game.
     pub fn levels<C: FromIterator<Level>>(&self) -> Result<C> {
         self.client.get_annotated_collection(format!("/games/{}/levels", self.id()))
     }
 
+    /// Returns the moderators of this game along with their roles.
+    ///
+    /// The users are requested with a single API request by embedding them into a request for the game.
+    pub fn moderators(&self) -> Result<Vec<(User, ModeratorRole)>> {
+        if self.data.moderators.len() > 1 {
+            Game::from_id_with_embeds(&self.client, self.id(), &Embeds::default().embed("moderators"))?;
+        }
+        self.data.moderators.iter()
+            .map(|(id, &role)| Ok((User::from_id(&self.client, id)?, role)))
+            .collect()
+    }
+
     /// Returns the different names registered for this game.
     pub fn names(&self) -> &Names {
         &self.data.names
     }
 
+    /// Returns the API IDs of the platforms this game can be run on.
+    pub fn platform_ids(&self) -> &[String] {
+        &self.data.platforms
+    }
+
+    /// Returns the platforms this game can be run on.
+    pub fn platforms<C: FromIterator<Platform>>(&self) -> Result<C> {
+        self.resolve("platforms", &self.data.platforms, |client, id| Platform::from_id(client, id))
+    }
+
+    /// Returns the API IDs of the publishers of this game.
+    pub fn publisher_ids(&self) -> &[String] {
+        &self.data.publishers
+    }
+
+    /// Returns the publishers of this game.
+    pub fn publishers<C: FromIterator<Publisher>>(&self) -> Result<C> {
+        self.resolve("publishers", &self.data.publishers, |client, id| Publisher::from_id(client, id))
+    }
+
+    /// Returns the API IDs of the regions this game can be run in.
+    pub fn region_ids(&self) -> &[String] {
+        &self.data.regions
+    }
+
+    /// Returns the regions this game can be run in.
+    pub fn regions<C: FromIterator<Region>>(&self) -> Result<C> {
+        self.resolve("regions", &self.data.regions, |client, id| Region::from_id(client, id))
+    }
+
+    /// Returns the date when this game was first released, if known.
+    pub fn release_date(&self) -> Option<NaiveDate> {
+        self.data.release_date
+    }
+
+    /// Returns the year in which this game was first released.
+    pub fn release_year(&self) -> u16 {
+        self.data.released
+    }
+
+    /// Returns a paginated list of games derived from this one, using the legacy `/romhacks` endpoint.
+    ///
+    /// This returns the same games as `derived_games` and exists for parity with the API.
+    pub fn romhacks(&self) -> PaginatedList<GameData> {
+        PaginatedList::new(self.client.clone(), format!("/games/{}/romhacks", self.id()))
+    }
+
+    /// Returns the rules that apply to all runs of this game.
+    pub fn ruleset(&self) -> Option<&Ruleset> {
+        self.data.ruleset.as_ref()
+    }
+
+    /// Returns the series this game belongs to, if any.
+    pub fn series(&self) -> Result<Option<Series>> {
+        Ok(match self.data.links.iter().find(|link| link.rel.as_ref().is_some_and(|rel| rel == "series")) {
+            Some(link) => Some(self.client.annotate(self.client.get_abs(link.uri.clone())?)),
+            None => None
+        })
+    }
+
     /// Returns the link to this game's page intended for humans.
     pub fn weblink(&self) -> &Url {
         &self.data.weblink
     }
 }
 
-/// Displays the game's English name.
+impl Game {
+    /// Resolves the given IDs of resources related to this game. If there is more than one, they are embedded in a single request for this game first, so that the individual lookups are cache hits.
+    fn resolve<T, C: FromIterator<T>>(&self, embed: &str, ids: &[String], from_id: impl Fn(&Client, &str) -> Result<T>) -> Result<C> {
+        if ids.len() > 1 {
+            Game::from_id_with_embeds(&self.client, self.id(), &Embeds::default().embed(embed))?;
+        }
+        ids.iter()
+            .map(|id| from_id(&self.client, id))
+            .collect()
+    }
+}
+
+/// Displays the game's English name, or its Japanese name if preferred by the client.
 impl fmt::Display for Game {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.data.names.international.fmt(f)
+        self.data.names.preferred(self.client.name_preference()).fmt(f)
+    }
+}
+
+eq_by_key!(Game, id);
+
+/// The cached data for a game returned by `Game::list`. This type is an implementation detail. You're probably looking for `BulkGame` instead.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(remote = "Self")]
+pub struct BulkGameData {
+    id: String,
+    abbreviation: String,
+    names: Names,
+    #[serde(with = "url_serde")]
+    weblink: Url,
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extra")]
+    extra: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip)]
+    raw: Option<std::sync::Arc<serde_json::Value>>
+}
+
+/// A game as returned by `Game::list`, which only includes a few of the game's fields.
+pub type BulkGame = AnnotatedData<BulkGameData>;
+
+impl Resource for BulkGameData {
+    fn path(&self) -> String {
+        format!("/games/{}", self.id)
     }
 }
+
+extensible!(BulkGameData);
+
+impl BulkGame {
+    /// Requests the full data for this game.
+    pub fn hydrate(&self) -> Result<Game> {
+        Game::from_id(&self.client, self.id())
+    }
+
+    /// Returns this game's abbreviation, as used in its URL on speedrun.com.
+    pub fn abbreviation(&self) -> &str {
+        &self.data.abbreviation
+    }
+
+    /// Returns this game's API ID.
+    pub fn id(&self) -> &str {
+        &self.data.id
+    }
+
+    /// Returns the different names registered for this game.
+    pub fn names(&self) -> &Names {
+        &self.data.names
+    }
+
+    /// Returns the link to this game's page intended for humans.
+    pub fn weblink(&self) -> &Url {
+        &self.data.weblink
+    }
+}
+
+/// Displays the game's English name, or its Japanese name if preferred by the client.
+impl fmt::Display for BulkGame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.data.names.preferred(self.client.name_preference()).fmt(f)
+    }
+}
+
+eq_by_key!(BulkGame, id);
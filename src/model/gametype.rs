@@ -0,0 +1,89 @@
+//! Gametypes are classifications for games which are not regular video games, like ROM hacks or fangames
+
+use {
+    std::fmt,
+    serde::{
+        Deserialize,
+        Serialize
+    },
+    crate::{
+        Result,
+        client::{
+            AnnotatedData,
+            Client,
+            Link,
+            Linked,
+            Resource
+        },
+        paginated::PaginatedList,
+        util::{
+            eq_by_key,
+            extensible
+        }
+    }
+};
+
+/// The cached data for a gametype. This type is an implementation detail. You're probably looking for `Gametype` instead.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(remote = "Self", rename_all = "kebab-case")]
+pub struct GametypeData {
+    id: String,
+    #[serde(default)]
+    links: Vec<Link>,
+    name: String,
+    allows_base_game: bool,
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extra")]
+    extra: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip)]
+    raw: Option<std::sync::Arc<serde_json::Value>>
+}
+
+/// Gametypes are classifications for games which are not regular video games, like ROM hacks or fangames.
+pub type Gametype = AnnotatedData<GametypeData>;
+
+impl Resource for GametypeData {
+    fn path(&self) -> String {
+        format!("/gametypes/{}", self.id)
+    }
+}
+
+impl Linked for GametypeData {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+extensible!(GametypeData);
+
+impl Gametype {
+    /// Returns a paginated list of all gametypes on speedrun.com.
+    pub fn list(client: impl Into<Client>) -> PaginatedList<GametypeData> {
+        PaginatedList::new(client.into(), "/gametypes".into())
+    }
+
+    /// Returns the gametype with the given ID.
+    pub fn from_id(client: &Client, id: impl fmt::Display) -> Result<Gametype> {
+        Ok(client.annotate(
+            client.get(format!("/gametypes/{}", id))?
+        ))
+    }
+
+    /// Returns `true` if games of this gametype can specify a base game they're derived from.
+    pub fn allows_base_game(&self) -> bool {
+        self.data.allows_base_game
+    }
+
+    /// Returns this gametype's API ID.
+    pub fn id(&self) -> &str {
+        &self.data.id
+    }
+}
+
+/// Displays the gametype name.
+impl fmt::Display for Gametype {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.data.name.fmt(f)
+    }
+}
+
+eq_by_key!(Gametype, id);
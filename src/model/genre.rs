@@ -0,0 +1,83 @@
+//! Genres are the categories of gameplay a game belongs to
+
+use {
+    std::fmt,
+    serde::{
+        Deserialize,
+        Serialize
+    },
+    crate::{
+        Result,
+        client::{
+            AnnotatedData,
+            Client,
+            Link,
+            Linked,
+            Resource
+        },
+        paginated::PaginatedList,
+        util::{
+            eq_by_key,
+            extensible
+        }
+    }
+};
+
+/// The cached data for a genre. This type is an implementation detail. You're probably looking for `Genre` instead.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(remote = "Self")]
+pub struct GenreData {
+    id: String,
+    #[serde(default)]
+    links: Vec<Link>,
+    name: String,
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extra")]
+    extra: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip)]
+    raw: Option<std::sync::Arc<serde_json::Value>>
+}
+
+/// Genres are the categories of gameplay a game belongs to.
+pub type Genre = AnnotatedData<GenreData>;
+
+impl Resource for GenreData {
+    fn path(&self) -> String {
+        format!("/genres/{}", self.id)
+    }
+}
+
+impl Linked for GenreData {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+extensible!(GenreData);
+
+impl Genre {
+    /// Returns a paginated list of all genres on speedrun.com.
+    pub fn list(client: impl Into<Client>) -> PaginatedList<GenreData> {
+        PaginatedList::new(client.into(), "/genres".into())
+    }
+
+    /// Returns the genre with the given ID.
+    pub fn from_id(client: &Client, id: impl fmt::Display) -> Result<Genre> {
+        Ok(client.annotate(
+            client.get(format!("/genres/{}", id))?
+        ))
+    }
+
+    /// Returns this genre's API ID.
+    pub fn id(&self) -> &str {
+        &self.data.id
+    }
+}
+
+/// Displays the genre name.
+impl fmt::Display for Genre {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.data.name.fmt(f)
+    }
+}
+
+eq_by_key!(Genre, id);
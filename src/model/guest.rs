@@ -0,0 +1,95 @@
+//! Guests are runners who participated in a run without having a registered account on speedrun.com
+
+use {
+    std::fmt,
+    reqwest::Url,
+    serde::{
+        Deserialize,
+        Serialize
+    },
+    crate::{
+        Result,
+        client::{
+            AnnotatedData,
+            Client,
+            Link,
+            Linked,
+            Resource
+        },
+        model::run::{
+            Run,
+            RunData,
+            RunsQuery
+        },
+        paginated::PaginatedList,
+        util::{
+            eq_by_key,
+            extensible,
+            path_segment
+        }
+    }
+};
+
+/// The cached data for a guest. This type is an implementation detail. You're probably looking for `Guest` instead.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(remote = "Self")]
+pub struct GuestData {
+    links: Vec<Link>,
+    name: String,
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extra")]
+    extra: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip)]
+    raw: Option<std::sync::Arc<serde_json::Value>>
+}
+
+/// Guests are runners who participated in a run without having a registered account on speedrun.com.
+pub type Guest = AnnotatedData<GuestData>;
+
+impl Resource for GuestData {
+    fn path(&self) -> String {
+        format!("/guests/{}", path_segment(&self.name))
+    }
+}
+
+impl Linked for GuestData {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+extensible!(GuestData);
+
+impl Guest {
+    /// Returns the guest with the given name.
+    pub fn from_name(client: &Client, name: impl fmt::Display) -> Result<Guest> {
+        Ok(client.annotate(
+            client.get(format!("/guests/{}", path_segment(&name.to_string())))?
+        ))
+    }
+
+    /// Returns the name under which this guest is listed.
+    pub fn name(&self) -> &str {
+        &self.data.name
+    }
+
+    /// Returns a paginated list of the runs this guest participated in.
+    pub fn runs(&self) -> PaginatedList<RunData> {
+        Run::search(&self.client, RunsQuery::default().guest(self.name()))
+    }
+
+    /// Returns the API URL listing this guest's runs, if the API returned one.
+    pub fn runs_uri(&self) -> Option<&Url> {
+        self.data.links.iter()
+            .find(|link| link.rel.as_ref().is_some_and(|rel| rel == "runs"))
+            .map(|link| &link.uri)
+    }
+}
+
+/// Displays the guest's name.
+impl fmt::Display for Guest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.data.name.fmt(f)
+    }
+}
+
+eq_by_key!(Guest, name);
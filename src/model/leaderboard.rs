@@ -0,0 +1,394 @@
+//! Leaderboards are the rankings of the current, non-obsoleted runs in a category or IL category
+
+use {
+    std::{
+        collections::BTreeMap,
+        iter::FromIterator
+    },
+    chrono::prelude::*,
+    reqwest::Url,
+    serde::{
+        Deserialize,
+        Serialize
+    },
+    crate::{
+        Result,
+        client::{
+            AnnotatedData,
+            Link,
+            Linked
+        },
+        embed::Embeds,
+        model::{
+            category::Category,
+            game::Game,
+            level::Level,
+            run::{
+                Run,
+                RunData,
+                TimingMethod
+            },
+            variable::Filter
+        },
+        util::extensible
+    }
+};
+#[cfg(feature = "csv")] use {
+    std::{
+        io,
+        time::Duration
+    },
+    crate::model::platform::Platform
+};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub(crate) struct LeaderboardEntryData {
+    pub(crate) place: usize,
+    pub(crate) run: RunData
+}
+
+/// A run together with its place on a leaderboard.
+#[derive(Debug, Clone)]
+pub struct LeaderboardEntry {
+    /// The run's place on the leaderboard. Tied runs share the same place.
+    pub place: usize,
+    /// The run itself.
+    pub run: Run
+}
+
+/// A serializable record of the places of the runs on a leaderboard at a point in time, used with `Leaderboard::diff`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct LeaderboardSnapshot {
+    /// The API ID of the leaderboard's category.
+    pub category: String,
+    /// For IL leaderboards, the API ID of the leaderboard's level.
+    pub level: Option<String>,
+    /// The variable/value pairs the leaderboard was filtered by, as a map from variable IDs to value IDs.
+    pub values: BTreeMap<String, String>,
+    /// The time when the snapshot was taken, according to the clock of the client it was taken with, see `Builder::clock`.
+    pub taken: DateTime<Utc>,
+    /// The runs on the leaderboard, in order.
+    pub entries: Vec<SnapshotEntry>
+}
+
+/// A run's place in a `LeaderboardSnapshot`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct SnapshotEntry {
+    /// The run's place on the leaderboard. Tied runs share the same place.
+    pub place: usize,
+    /// The run's API ID.
+    pub run_id: String
+}
+
+/// A run whose place differs between two snapshots. Part of a `LeaderboardDiff`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaceChange {
+    /// The run's API ID.
+    pub run_id: String,
+    /// The run's place in the older snapshot.
+    pub old_place: usize,
+    /// The run's place in the newer snapshot.
+    pub new_place: usize
+}
+
+/// The differences between two snapshots of a leaderboard, returned by `Leaderboard::diff`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LeaderboardDiff {
+    /// Runs which are only on the newer leaderboard, along with their new places.
+    pub new_runs: Vec<SnapshotEntry>,
+    /// Runs which are only on the older leaderboard, usually because they have been obsoleted, along with their old places.
+    pub removed_runs: Vec<SnapshotEntry>,
+    /// Runs which are on both leaderboards but at different places.
+    pub place_changes: Vec<PlaceChange>
+}
+
+impl LeaderboardDiff {
+    /// Returns `true` if the leaderboard did not change.
+    pub fn is_empty(&self) -> bool {
+        self.new_runs.is_empty() && self.removed_runs.is_empty() && self.place_changes.is_empty()
+    }
+}
+
+/// Additional options for requesting a leaderboard, used with `ToLeaderboard::leaderboard_with_options`.
+///
+/// The default options return the leaderboard as it appears on speedrun.com by default.
+#[derive(Debug, Default, Clone)]
+pub struct LeaderboardOptions {
+    embeds: Embeds,
+    params: Vec<(&'static str, String)>,
+    pub(crate) validate: bool
+}
+
+impl LeaderboardOptions {
+    fn param(mut self, key: &'static str, value: impl ToString) -> LeaderboardOptions {
+        self.params.retain(|&(existing_key, _)| existing_key != key);
+        self.params.push((key, value.to_string()));
+        self
+    }
+
+    /// Only returns the top `top` places. Due to ties, this can return more than `top` runs.
+    pub fn top(self, top: usize) -> LeaderboardOptions {
+        self.param("top", top)
+    }
+
+    /// Only returns runs done on the platform with the given ID.
+    pub fn platform(self, id: impl ToString) -> LeaderboardOptions {
+        self.param("platform", id)
+    }
+
+    /// Only returns runs done in the region with the given ID.
+    pub fn region(self, id: impl ToString) -> LeaderboardOptions {
+        self.param("region", id)
+    }
+
+    /// If `true`, only returns runs done on emulator. If `false`, only returns runs done on real devices.
+    pub fn emulators(self, emulators: bool) -> LeaderboardOptions {
+        self.param("emulators", emulators)
+    }
+
+    /// If `true`, only returns runs with a video.
+    pub fn video_only(self, video_only: bool) -> LeaderboardOptions {
+        self.param("video-only", video_only)
+    }
+
+    /// Ranks the runs by the given timing method instead of the game's default.
+    pub fn timing(self, timing: TimingMethod) -> LeaderboardOptions {
+        self.param("timing", timing.as_str())
+    }
+
+    /// Returns the leaderboard as it was on the given date, i.e. ignoring runs done after that date.
+    pub fn date(self, date: NaiveDate) -> LeaderboardOptions {
+        self.param("date", date)
+    }
+
+    /// If `true`, the filter is checked using `Filter::validate` before requesting the leaderboard, so that invalid variable/value pairs cause an error instead of an unexpectedly unfiltered or empty leaderboard.
+    ///
+    /// This costs an additional API request for the category's variables, unless they're cached.
+    pub fn validate(self, validate: bool) -> LeaderboardOptions {
+        LeaderboardOptions { validate, ..self }
+    }
+
+    /// Requests the given resources to be embedded in the response.
+    ///
+    /// The players of the runs on the leaderboard are always embedded, so that `Run::runners` does not need to make any API requests for runs on the leaderboard. See the `embed` module for details.
+    pub fn embeds(self, embeds: &Embeds) -> LeaderboardOptions {
+        LeaderboardOptions {
+            embeds: embeds.clone(),
+            ..self
+        }
+    }
+
+    pub(crate) fn query(&self, filter: &Filter) -> Vec<(String, String)> {
+        self.params.iter()
+            .map(|(key, value)| ((*key).to_owned(), value.clone()))
+            .chain(self.embeds.clone().embed("players").query())
+            .chain(filter.into_iter().map(|(key, value)| (key.clone(), value.clone())))
+            .collect()
+    }
+}
+
+/// The cached data for a leaderboard. This type is an implementation detail. You're probably looking for `Leaderboard` instead.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(remote = "Self")]
+pub struct LeaderboardData {
+    category: String,
+    #[serde(default)]
+    emulators: Option<bool>,
+    game: String,
+    level: Option<String>,
+    #[serde(default)]
+    links: Vec<Link>,
+    #[serde(default)]
+    platform: Option<String>,
+    #[serde(default)]
+    region: Option<String>,
+    pub(crate) runs: Vec<LeaderboardEntryData>,
+    timing: TimingMethod,
+    values: BTreeMap<String, String>,
+    #[serde(default, rename = "video-only")]
+    video_only: bool,
+    #[serde(with = "url_serde")]
+    weblink: Url,
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extra")]
+    extra: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip)]
+    raw: Option<std::sync::Arc<serde_json::Value>>
+}
+
+/// Leaderboards are the rankings of the current, non-obsoleted runs in a category or IL category.
+///
+/// Leaderboards can be obtained using the methods of the `ToLeaderboard` trait.
+pub type Leaderboard = AnnotatedData<LeaderboardData>;
+
+impl Linked for LeaderboardData {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+extensible!(LeaderboardData);
+
+impl Leaderboard {
+    /// Returns the category of this leaderboard.
+    pub fn category(&self) -> Result<Category> {
+        Category::from_id(&self.client, &self.data.category)
+    }
+
+    /// Returns the API ID of this leaderboard's category.
+    pub fn category_id(&self) -> &str {
+        &self.data.category
+    }
+
+    /// Returns the runs on this leaderboard along with their places.
+    pub fn entries<C: FromIterator<LeaderboardEntry>>(&self) -> C {
+        self.data.runs.iter()
+            .map(|entry| LeaderboardEntry {
+                place: entry.place,
+                run: self.client.annotate(entry.run.clone())
+            })
+            .collect()
+    }
+
+    /// Returns whether this leaderboard was filtered to only include runs done on emulators (`Some(true)`) or on real hardware (`Some(false)`). Returns `None` if it wasn't filtered by this.
+    pub fn emulators(&self) -> Option<bool> {
+        self.data.emulators
+    }
+
+    /// Returns the game of this leaderboard.
+    pub fn game(&self) -> Result<Game> {
+        Game::from_id(&self.client, &self.data.game)
+    }
+
+    /// Returns the API ID of this leaderboard's game.
+    pub fn game_id(&self) -> &str {
+        &self.data.game
+    }
+
+    /// For IL leaderboards, returns the level of this leaderboard. Returns `Ok(None)` for full-game leaderboards.
+    pub fn level(&self) -> Result<Option<Level>> {
+        self.data.level.as_ref().map(|level_id| Level::from_id(&self.client, level_id)).transpose()
+    }
+
+    /// For IL leaderboards, returns the API ID of this leaderboard's level.
+    pub fn level_id(&self) -> Option<&str> {
+        self.data.level.as_deref()
+    }
+
+    /// Returns the API ID of the platform this leaderboard was filtered by, if any.
+    pub fn platform_id(&self) -> Option<&str> {
+        self.data.platform.as_deref()
+    }
+
+    /// Returns the API ID of the region this leaderboard was filtered by, if any.
+    pub fn region_id(&self) -> Option<&str> {
+        self.data.region.as_deref()
+    }
+
+    /// Returns the runs on this leaderboard, in order.
+    pub fn runs<C: FromIterator<Run>>(&self) -> C {
+        self.data.runs.iter()
+            .map(|entry| self.client.annotate(entry.run.clone()))
+            .collect()
+    }
+
+    /// Returns a serializable record of the places of the runs on this leaderboard, for comparing with later versions using `Leaderboard::diff`.
+    pub fn snapshot(&self) -> LeaderboardSnapshot {
+        LeaderboardSnapshot {
+            category: self.data.category.clone(),
+            level: self.data.level.clone(),
+            values: self.data.values.clone(),
+            taken: self.client.clock().now().into(),
+            entries: self.entries::<Vec<_>>().into_iter()
+                .map(|entry| SnapshotEntry {
+                    place: entry.place,
+                    run_id: entry.run.id().to_owned()
+                })
+                .collect()
+        }
+    }
+
+    /// Compares two snapshots of a leaderboard and reports new runs, removed runs, and runs whose place changed.
+    ///
+    /// Runs are matched by ID. The snapshots are not checked to be of the same leaderboard.
+    pub fn diff(old: &LeaderboardSnapshot, new: &LeaderboardSnapshot) -> LeaderboardDiff {
+        let old_places = old.entries.iter().map(|entry| (&entry.run_id, entry.place)).collect::<BTreeMap<_, _>>();
+        let new_places = new.entries.iter().map(|entry| (&entry.run_id, entry.place)).collect::<BTreeMap<_, _>>();
+        LeaderboardDiff {
+            new_runs: new.entries.iter().filter(|entry| !old_places.contains_key(&entry.run_id)).cloned().collect(),
+            removed_runs: old.entries.iter().filter(|entry| !new_places.contains_key(&entry.run_id)).cloned().collect(),
+            place_changes: new.entries.iter()
+                .filter_map(|entry| old_places.get(&entry.run_id)
+                    .filter(|&&old_place| old_place != entry.place)
+                    .map(|&old_place| PlaceChange {
+                        run_id: entry.run_id.clone(),
+                        old_place,
+                        new_place: entry.place
+                    })
+                )
+                .collect()
+        }
+    }
+
+    /// Returns the timing method by which the runs on this leaderboard are ranked.
+    pub fn timing(&self) -> TimingMethod {
+        self.data.timing
+    }
+
+    /// Returns the variable/value pairs this leaderboard was filtered by, as a map from variable IDs to value IDs.
+    pub fn values(&self) -> &BTreeMap<String, String> {
+        &self.data.values
+    }
+
+    /// Returns `true` if this leaderboard was filtered to only include runs with a video.
+    pub fn video_only(&self) -> bool {
+        self.data.video_only
+    }
+
+    /// Writes this leaderboard to the given writer in CSV format, with a header row.
+    ///
+    /// The columns are the place, the runners' names (comma-separated), the times in each timing method in seconds, the date, the platform name, and one column per variable used by the runs, containing the value labels.
+    ///
+    /// This makes additional API requests for the runners, platforms, and variables, most of which are answered from the cache.
+    #[cfg(feature = "csv")]
+    pub fn write_csv(&self, writer: impl io::Write) -> Result<()> {
+        fn format_time(time: Option<Duration>) -> String {
+            time.map(|time| format!("{}.{:03}", time.as_secs(), time.subsec_millis())).unwrap_or_default()
+        }
+
+        let entries = self.entries::<Vec<_>>();
+        let values = entries.iter().map(|entry| entry.run.values()).collect::<Result<Vec<_>>>()?;
+        let variables = values.iter().flatten().map(|(variable, _)| (variable.id().to_owned(), variable.to_string())).collect::<BTreeMap<_, _>>();
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(
+            ["place", "runners", "realtime", "realtime_noloads", "ingame", "date", "platform"].iter().map(|&column| column.to_owned())
+                .chain(variables.values().cloned())
+        )?;
+        for (entry, values) in entries.iter().zip(values) {
+            let times = entry.run.times();
+            let platform = entry.run.platform_id().map(|platform_id| Platform::from_id(&self.client, platform_id)).transpose()?;
+            writer.write_record(
+                vec![
+                    entry.place.to_string(),
+                    entry.run.runners()?.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "),
+                    format_time(times.realtime),
+                    format_time(times.realtime_noloads),
+                    format_time(times.ingame),
+                    entry.run.date().map(|date| date.to_string()).unwrap_or_default(),
+                    platform.map(|platform| platform.to_string()).unwrap_or_default()
+                ].into_iter()
+                    .chain(variables.keys().map(|variable_id| values.iter()
+                        .find(|(variable, _)| variable.id() == variable_id)
+                        .map(|(_, value)| value.label().to_owned())
+                        .unwrap_or_default()
+                    ))
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Returns the URL to the leaderboard's page on speedrun.com.
+    pub fn weblink(&self) -> &Url {
+        &self.data.weblink
+    }
+}
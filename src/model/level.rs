@@ -6,39 +6,81 @@ use {
         iter::FromIterator
     },
     itertools::Itertools,
-    serde::Deserialize,
+    reqwest::Url,
+    serde::{
+        Deserialize,
+        Serialize
+    },
     crate::{
         Error,
         Result,
         client::{
             AnnotatedData,
             Client,
-            Link
+            Link,
+            Linked,
+            Resource
         },
+        embed::Embeds,
         model::{
             category::{
                 Category,
-                Leaderboard,
                 ToLeaderboard
             },
             game::Game,
-            run::Run,
+            leaderboard::{
+                Leaderboard,
+                LeaderboardOptions
+            },
+            run::{
+                Run,
+                RunData,
+                RunsQuery
+            },
             variable::Filter
+        },
+        paginated::PaginatedList,
+        util::{
+            ResultExt,
+            eq_by_key,
+            extensible
         }
     }
 };
 
 /// The cached data for a level. This type is an implementation detail. You're probably looking for `Level` instead.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(remote = "Self")]
 pub struct LevelData {
     id: String,
     links: Vec<Link>,
-    name: String
+    name: String,
+    rules: Option<String>,
+    #[serde(with = "url_serde")]
+    weblink: Url,
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extra")]
+    extra: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip)]
+    raw: Option<std::sync::Arc<serde_json::Value>>
 }
 
 /// Levels are the stages/worlds/maps within a game.
 pub type Level = AnnotatedData<LevelData>;
 
+impl Resource for LevelData {
+    fn path(&self) -> String {
+        format!("/levels/{}", self.id)
+    }
+}
+
+impl Linked for LevelData {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+extensible!(LevelData);
+
 impl Level {
     /// Returns the level with the given ID.
     pub fn from_id(client: &Client, id: impl fmt::Display) -> Result<Level> {
@@ -47,13 +89,23 @@ impl Level {
         ))
     }
 
+    /// Returns the level with the given ID, requesting the given resources to be embedded in the response.
+    ///
+    /// See the `embed` module for details.
+    pub fn from_id_with_embeds(client: &Client, id: impl fmt::Display, embeds: &Embeds) -> Result<Level> {
+        Ok(client.annotate(
+            client.get_query(format!("/levels/{}", id), embeds.query())?
+        ))
+    }
+
     /// Returns the game to which this level belongs.
     pub fn game(&self) -> Result<Game> {
+        const OPERATION: &str = "Level::game";
         let (link,) = self.data.links.iter()
-            .filter(|link| link.rel.as_ref().map_or(false, |rel| rel == "game"))
-            .collect_tuple().ok_or(Error::MissingGameRel)?;
+            .filter(|link| link.rel.as_ref().is_some_and(|rel| rel == "game"))
+            .collect_tuple().ok_or(Error::MissingGameRel).context(OPERATION)?;
         Ok(self.client.annotate(
-            self.client.get_abs(link.uri.clone())?
+            self.client.get_abs(link.uri.clone()).context(OPERATION)?
         ))
     }
 
@@ -61,6 +113,33 @@ impl Level {
     pub fn id(&self) -> &str {
         &self.data.id
     }
+
+    /// Returns the rules specific to this level, if any.
+    pub fn rules(&self) -> Option<&str> {
+        self.data.rules.as_deref()
+    }
+
+    /// Returns a paginated list of all runs done in this level which match the given search parameters, including obsoleted and unverified runs.
+    pub fn runs(&self, query: RunsQuery) -> PaginatedList<RunData> {
+        Run::search(self.client.clone(), query.level(self.id()))
+    }
+
+    /// Returns the top `top` places of the leaderboard of each IL category applicable to this level.
+    ///
+    /// If `skip_empty` is `true`, categories without any verified runs are omitted.
+    pub fn records<C: FromIterator<Leaderboard>>(&self, top: u8, skip_empty: bool) -> Result<C> {
+        Ok(
+            self.client.get_query::<_, _, _, _, Vec<_>>(format!("/levels/{}/records", self.id()), &[("top", top.to_string()), ("skip-empty", skip_empty.to_string()), ("max", "200".to_owned())])?
+                .into_iter()
+                .map(|data| self.client.annotate(data))
+                .collect()
+        )
+    }
+
+    /// Returns the link to this level's leaderboard on speedrun.com.
+    pub fn weblink(&self) -> &Url {
+        &self.data.weblink
+    }
 }
 
 /// Displays the level name.
@@ -70,41 +149,20 @@ impl fmt::Display for Level {
     }
 }
 
+eq_by_key!(Level, id);
+
 impl ToLeaderboard for (&Level, &Category) {
-    /// Returns a leaderboard for this IL category, filtered by the given variable/value pairs.
+    /// Returns a leaderboard for this IL category, filtered by the given variable/value pairs and additional options, including the places of the runs and other metadata.
     ///
     /// # Errors
     ///
     /// Will error if the category is a full-game category.
-    fn filtered_leaderboard<C: FromIterator<Run>>(self, filter: &Filter) -> Result<C> {
-        let (level, category) = self;
-        Ok(
-            level.client.get_query::<_, _, _, _, Leaderboard>(format!("/leaderboards/{}/level/{}/{}", level.game()?.id(), level.id(), category.id()), filter)?
-                .runs
-                .into_iter()
-                .map(|entry| level.client.annotate(entry.run))
-                .collect()
-        )
-    }
-
-    /// A convenience method returning the first place from a filtered version of this IL category's leaderboard.
-    ///
-    /// If the world record is tied, this method returns whichever run the API lists first.
-    ///
-    /// If no run has been verified for the given level, category, and filter, `Ok(None)` is returned.
-    fn filtered_wr(self, filter: &Filter) -> Result<Option<Run>> {
+    fn leaderboard_with_options(self, filter: &Filter, options: &LeaderboardOptions) -> Result<Leaderboard> {
         let (level, category) = self;
-        let mut lb = level.client.get_query::<_, _, _, _, Leaderboard>(format!("/leaderboards/{}/level/{}/{}", level.game()?.id(), level.id(), category.id()), filter)?
-            .runs;
-        if lb.is_empty() { return Ok(None); }
-        Ok(Some(level.client.annotate(lb.remove(0).run)))
-    }
-
-    /// Returns true if the world record for this level, category, and filter is tied.
-    fn filtered_wr_is_tied(self, filter: &Filter) -> Result<bool> {
-        let (level, category) = self;
-        let lb = level.client.get_query::<_, _, _, _, Leaderboard>(format!("/leaderboards/{}/level/{}/{}", level.game()?.id(), level.id(), category.id()), filter)?
-            .runs;
-        Ok(lb.len() > 1 && lb[1].place == 1)
+        const OPERATION: &str = "Level::leaderboard_with_options";
+        if options.validate { filter.validate(category).context(OPERATION)?; }
+        Ok(level.client.annotate(
+            level.client.get_query(format!("/leaderboards/{}/level/{}/{}", level.game().context(OPERATION)?.id(), level.id(), category.id()), options.query(filter)).context(OPERATION)?
+        ))
     }
 }
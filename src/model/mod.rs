@@ -1,9 +1,193 @@
 //! Representations of data types returned by the API
 
 pub mod category;
+pub mod developer;
+pub mod engine;
 pub mod game;
+pub mod gametype;
+pub mod genre;
+pub mod guest;
+pub mod leaderboard;
 pub mod level;
 pub mod notification;
+pub mod platform;
+pub mod publisher;
+pub mod region;
 pub mod run;
+pub mod series;
 pub mod user;
 pub mod variable;
+
+#[cfg(test)]
+mod tests {
+    use {
+        serde::de::DeserializeOwned,
+        serde_json::Value,
+        crate::{
+            embed,
+            util::{
+                DeserializeOptions,
+                with_options
+            }
+        },
+        super::{
+            game::GameData,
+            leaderboard::LeaderboardData,
+            run::RunData,
+            user::UserData
+        }
+    };
+
+    const GAME: &str = r#"{
+        "id": "xldev513",
+        "names": {"international": "Super Mario Sunshine", "japanese": "スーパーマリオサンシャイン", "twitch": "Super Mario Sunshine"},
+        "boostReceivedCount": 0,
+        "boostDistinctDonorsCount": 0,
+        "abbreviation": "sms",
+        "weblink": "https://www.speedrun.com/sms",
+        "discord": "",
+        "released": 2002,
+        "release-date": "2002-07-19",
+        "ruleset": {
+            "show-milliseconds": false,
+            "require-verification": true,
+            "require-video": false,
+            "run-times": ["realtime", "realtime_noloads"],
+            "default-time": "realtime",
+            "emulators-allowed": false
+        },
+        "romhack": false,
+        "gametypes": [],
+        "platforms": ["w89rwelk"],
+        "regions": ["pr184lqn", "e6lxy1dz"],
+        "genres": [],
+        "engines": [],
+        "developers": [],
+        "publishers": [],
+        "moderators": {"zxzno4jp": "super-moderator", "8rpvq4jv": "moderator"},
+        "created": null,
+        "assets": {
+            "logo": {"uri": "https://www.speedrun.com/static/game/xldev513/logo.png", "width": 150, "height": 40},
+            "cover-tiny": null,
+            "trophy-1st": {"uri": "https://www.speedrun.com/static/game/xldev513/1st.png", "width": 64, "height": 64},
+            "background": null,
+            "foreground": null
+        },
+        "links": [
+            {"rel": "self", "uri": "https://www.speedrun.com/api/v1/games/xldev513"},
+            {"rel": "runs", "uri": "https://www.speedrun.com/api/v1/runs?game=xldev513"}
+        ]
+    }"#;
+
+    const RUN: &str = r#"{
+        "id": "90y6pm7e",
+        "weblink": "https://www.speedrun.com/run/90y6pm7e",
+        "game": "m1zky010",
+        "level": null,
+        "category": "wdmzz5kq",
+        "videos": {"links": [{"uri": "https://www.youtube.com/watch?v=8xbTg6Gbv00"}]},
+        "comment": "Aikido ftw",
+        "status": {"status": "verified", "examiner": "pj0v90mx", "verify-date": "2015-07-29T00:37:24Z"},
+        "players": [{"rel": "user", "id": "wzx7q875", "uri": "https://www.speedrun.com/api/v1/users/wzx7q875"}],
+        "date": "2015-07-28",
+        "submitted": "2015-07-28T18:33:56Z",
+        "times": {
+            "primary": "PT1H53M58S",
+            "primary_t": 6838,
+            "realtime": "PT1H53M58S",
+            "realtime_t": 6838,
+            "realtime_noloads": null,
+            "realtime_noloads_t": 0,
+            "ingame": null,
+            "ingame_t": 0
+        },
+        "system": {"platform": "nzelreqp", "emulated": false, "region": "pr184lqn"},
+        "splits": null,
+        "values": {"ylq9qkv8": "21d4zvp1"},
+        "links": [
+            {"rel": "self", "uri": "https://www.speedrun.com/api/v1/runs/90y6pm7e"},
+            {"rel": "game", "uri": "https://www.speedrun.com/api/v1/games/m1zky010"}
+        ]
+    }"#;
+
+    const USER: &str = r##"{
+        "id": "wzx7q875",
+        "names": {"international": "Pac", "japanese": null},
+        "supporterAnimation": false,
+        "pronouns": "He/Him",
+        "weblink": "https://www.speedrun.com/user/Pac",
+        "name-style": {"style": "solid", "color": {"light": "#EE2222", "dark": "#EE4444"}},
+        "role": "user",
+        "signup": "2014-03-18T23:02:33Z",
+        "location": {
+            "country": {"code": "de", "names": {"international": "Germany", "japanese": "ドイツ"}},
+            "region": null
+        },
+        "twitch": {"uri": "https://www.twitch.tv/pac"},
+        "hitbox": null,
+        "youtube": null,
+        "twitter": null,
+        "speedrunslive": {"uri": "https://www.speedrunslive.com/profiles/#!/Pac/1"},
+        "assets": {
+            "icon": {"uri": null},
+            "supporterIcon": null,
+            "image": {"uri": "https://www.speedrun.com/static/user/wzx7q875/image.png"}
+        },
+        "links": [
+            {"rel": "self", "uri": "https://www.speedrun.com/api/v1/users/wzx7q875"},
+            {"rel": "runs", "uri": "https://www.speedrun.com/api/v1/runs?user=wzx7q875"}
+        ]
+    }"##;
+
+    fn strict<T: DeserializeOwned>(json: &str) -> serde_json::Result<T> {
+        with_options(DeserializeOptions { strict: true, keep_raw_json: false }, || serde_json::from_str(json))
+    }
+
+    #[test]
+    fn strict_game() -> serde_json::Result<()> {
+        strict::<GameData>(GAME)?;
+        Ok(())
+    }
+
+    #[test]
+    fn strict_run() -> serde_json::Result<()> {
+        strict::<RunData>(RUN)?;
+        Ok(())
+    }
+
+    #[test]
+    fn strict_user() -> serde_json::Result<()> {
+        strict::<UserData>(USER)?;
+        Ok(())
+    }
+
+    #[test]
+    fn strict_leaderboard() -> serde_json::Result<()> {
+        // leaderboards are always requested with embedded players
+        let mut leaderboard = serde_json::from_str::<Value>(&format!(r#"{{
+            "weblink": "https://www.speedrun.com/sms#Any",
+            "game": "xldev513",
+            "category": "wdmzz5kq",
+            "level": null,
+            "platform": null,
+            "region": null,
+            "emulators": null,
+            "video-only": false,
+            "timing": "realtime",
+            "values": {{}},
+            "runs": [{{"place": 1, "run": {}}}],
+            "links": [
+                {{"rel": "game", "uri": "https://www.speedrun.com/api/v1/games/xldev513"}},
+                {{"rel": "category", "uri": "https://www.speedrun.com/api/v1/categories/wdmzz5kq"}}
+            ],
+            "players": {{"data": [{}]}}
+        }}"#, RUN, USER.replacen('{', r#"{"rel": "user","#, 1)))?;
+        let embedded = embed::extract("leaderboards", &mut leaderboard);
+        assert_eq!(embedded.len(), 1);
+        for (_, user) in &embedded {
+            strict::<UserData>(&user["data"].to_string())?;
+        }
+        strict::<LeaderboardData>(&leaderboard.to_string())?;
+        Ok(())
+    }
+}
@@ -2,24 +2,41 @@
 
 use {
     std::{
+        collections::VecDeque,
         fmt,
-        iter::FromIterator
+        iter::FromIterator,
+        time::{
+            Duration,
+            SystemTime
+        }
     },
     chrono::prelude::*,
     reqwest::Url,
-    serde::Deserialize,
+    serde::{
+        Deserialize,
+        Serialize
+    },
     crate::{
         Result,
         client::{
             AnnotatedData,
             Auth,
             Client
+        },
+        paginated::PaginatedList,
+        util::{
+            RecentlySeen,
+            eq_by_key,
+            extensible
         }
     }
 };
 
+/// The number of polls for which a notification is remembered after it was last listed by the API, so that it's not yielded again.
+const SEEN_POLLS: usize = 10;
+
 /// The kind of link contained in a notification. Returned by `Notification::webllink_rel`.
-#[derive(Debug, Deserialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
 #[serde(rename_all = "snake_case")]
 pub enum Rel {
     /// someone liked the forum post
@@ -32,14 +49,14 @@ pub enum Rel {
     Guide
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 struct Item {
     rel: Rel,
     #[serde(with = "url_serde")]
     uri: Url
 }
 
-#[derive(Debug, Deserialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
 #[serde(rename_all = "snake_case")]
 enum ReadStatus {
     Read,
@@ -56,24 +73,51 @@ impl From<ReadStatus> for bool {
 }
 
 /// The cached data for a notification. This type is an implementation detail. You're probably looking for `Notification` instead.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(remote = "Self")]
 pub struct NotificationData {
     id: String,
     created: DateTime<Utc>,
     item: Item,
     status: ReadStatus,
-    text: String
+    text: String,
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extra")]
+    extra: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip)]
+    raw: Option<std::sync::Arc<serde_json::Value>>
 }
 
 /// Notifications are system-generated messages sent to users when certain events concerning them happen on the site, like somebody liking a post or a run being verified.
 pub type Notification = AnnotatedData<NotificationData, Auth>;
 
+extensible!(NotificationData);
+
 impl Notification {
     /// Returns a paginated list of all games on speedrun.com.
     pub fn list<C: FromIterator<Notification>>(client: &Client<Auth>) -> Result<C> {
         client.get_annotated_collection("/notifications")
     }
 
+    /// Returns a paginated list of all of the authenticated user's notifications, including those which are not on the first page returned by `list`.
+    pub fn all(client: &Client<Auth>) -> PaginatedList<NotificationData, Auth> {
+        PaginatedList::new(client.clone(), "/notifications".into())
+    }
+
+    /// Returns an iterator which periodically checks for new notifications, at most once per `interval`, and yields each notification only once.
+    ///
+    /// Notifications are tracked by ID, so a notification yielded by a previous poll is not yielded again while it's still listed by the API. The first poll yields all notifications currently listed, use `Notification::read` to skip those which have already been read.
+    ///
+    /// The iterator blocks until a new notification is available and never ends.
+    pub fn poll(client: &Client<Auth>, interval: Duration) -> NotificationPoller {
+        NotificationPoller {
+            client: client.clone(),
+            interval,
+            last_poll: None,
+            pending: VecDeque::default(),
+            seen: RecentlySeen::new(SEEN_POLLS)
+        }
+    }
+
     /// Returns this notification's API ID.
     pub fn id(&self) -> &str {
         &self.data.id
@@ -106,3 +150,47 @@ impl fmt::Display for Notification {
         self.data.text.fmt(f)
     }
 }
+
+eq_by_key!(Notification, id);
+
+/// An iterator over new notifications, returned by `Notification::poll`.
+#[derive(Debug)]
+pub struct NotificationPoller {
+    client: Client<Auth>,
+    interval: Duration,
+    last_poll: Option<SystemTime>,
+    pending: VecDeque<Notification>,
+    seen: RecentlySeen<String>
+}
+
+impl Iterator for NotificationPoller {
+    type Item = Result<Notification>;
+
+    fn next(&mut self) -> Option<Result<Notification>> {
+        loop {
+            if let Some(notification) = self.pending.pop_front() {
+                return Some(Ok(notification));
+            }
+            if let Some(last_poll) = self.last_poll {
+                let clock = self.client.clock();
+                // if the clock went backwards, wait for the full interval
+                let elapsed = clock.now().duration_since(last_poll).unwrap_or_default();
+                if elapsed < self.interval {
+                    clock.sleep(self.interval - elapsed);
+                }
+            }
+            self.last_poll = Some(self.client.clock().now());
+            let notifications = match self.client.fresh().get::<_, Vec<NotificationData>>("/notifications") {
+                Ok(notifications) => notifications,
+                Err(e) => return Some(Err(e))
+            };
+            self.seen.start_poll();
+            // the API lists the newest notifications first, but they should be yielded in chronological order
+            for data in notifications.into_iter().rev() {
+                if self.seen.insert(data.id.clone()) {
+                    self.pending.push_back(self.client.annotate(data));
+                }
+            }
+        }
+    }
+}
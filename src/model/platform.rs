@@ -0,0 +1,112 @@
+//! Platforms are the consoles or other systems games can be played on
+
+use {
+    std::fmt,
+    serde::{
+        Deserialize,
+        Serialize
+    },
+    crate::{
+        Result,
+        client::{
+            AnnotatedData,
+            Client,
+            Link,
+            Linked,
+            Resource
+        },
+        paginated::{
+            PaginatedList,
+            SortKey
+        },
+        util::{
+            eq_by_key,
+            extensible
+        }
+    }
+};
+
+/// The fields by which the list of platforms can be sorted, used with `PaginatedList::order_by`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderBy {
+    /// Sort by name. This is the default.
+    Name,
+    /// Sort by release year.
+    Released
+}
+
+impl SortKey for OrderBy {
+    type Item = PlatformData;
+
+    fn param_value(&self) -> &'static str {
+        match self {
+            OrderBy::Name => "name",
+            OrderBy::Released => "released"
+        }
+    }
+}
+
+/// The cached data for a platform. This type is an implementation detail. You're probably looking for `Platform` instead.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(remote = "Self")]
+pub struct PlatformData {
+    id: String,
+    #[serde(default)]
+    links: Vec<Link>,
+    name: String,
+    released: u16,
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extra")]
+    extra: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip)]
+    raw: Option<std::sync::Arc<serde_json::Value>>
+}
+
+/// Platforms are the consoles or other systems games can be played on.
+pub type Platform = AnnotatedData<PlatformData>;
+
+impl Resource for PlatformData {
+    fn path(&self) -> String {
+        format!("/platforms/{}", self.id)
+    }
+}
+
+impl Linked for PlatformData {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+extensible!(PlatformData);
+
+impl Platform {
+    /// Returns a paginated list of all platforms on speedrun.com.
+    pub fn list(client: impl Into<Client>) -> PaginatedList<PlatformData> {
+        PaginatedList::new(client.into(), "/platforms".into())
+    }
+
+    /// Returns the platform with the given ID.
+    pub fn from_id(client: &Client, id: impl fmt::Display) -> Result<Platform> {
+        Ok(client.annotate(
+            client.get(format!("/platforms/{}", id))?
+        ))
+    }
+
+    /// Returns this platform's API ID.
+    pub fn id(&self) -> &str {
+        &self.data.id
+    }
+
+    /// Returns the year in which this platform was released.
+    pub fn release_year(&self) -> u16 {
+        self.data.released
+    }
+}
+
+/// Displays the platform name.
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.data.name.fmt(f)
+    }
+}
+
+eq_by_key!(Platform, id);
@@ -0,0 +1,83 @@
+//! Publishers are the companies who published a game
+
+use {
+    std::fmt,
+    serde::{
+        Deserialize,
+        Serialize
+    },
+    crate::{
+        Result,
+        client::{
+            AnnotatedData,
+            Client,
+            Link,
+            Linked,
+            Resource
+        },
+        paginated::PaginatedList,
+        util::{
+            eq_by_key,
+            extensible
+        }
+    }
+};
+
+/// The cached data for a publisher. This type is an implementation detail. You're probably looking for `Publisher` instead.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(remote = "Self")]
+pub struct PublisherData {
+    id: String,
+    #[serde(default)]
+    links: Vec<Link>,
+    name: String,
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extra")]
+    extra: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip)]
+    raw: Option<std::sync::Arc<serde_json::Value>>
+}
+
+/// Publishers are the companies who published a game.
+pub type Publisher = AnnotatedData<PublisherData>;
+
+impl Resource for PublisherData {
+    fn path(&self) -> String {
+        format!("/publishers/{}", self.id)
+    }
+}
+
+impl Linked for PublisherData {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+extensible!(PublisherData);
+
+impl Publisher {
+    /// Returns a paginated list of all publishers on speedrun.com.
+    pub fn list(client: impl Into<Client>) -> PaginatedList<PublisherData> {
+        PaginatedList::new(client.into(), "/publishers".into())
+    }
+
+    /// Returns the publisher with the given ID.
+    pub fn from_id(client: &Client, id: impl fmt::Display) -> Result<Publisher> {
+        Ok(client.annotate(
+            client.get(format!("/publishers/{}", id))?
+        ))
+    }
+
+    /// Returns this publisher's API ID.
+    pub fn id(&self) -> &str {
+        &self.data.id
+    }
+}
+
+/// Displays the publisher name.
+impl fmt::Display for Publisher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.data.name.fmt(f)
+    }
+}
+
+eq_by_key!(Publisher, id);
@@ -0,0 +1,83 @@
+//! Regions are the geographic regions in which game releases are sold
+
+use {
+    std::fmt,
+    serde::{
+        Deserialize,
+        Serialize
+    },
+    crate::{
+        Result,
+        client::{
+            AnnotatedData,
+            Client,
+            Link,
+            Linked,
+            Resource
+        },
+        paginated::PaginatedList,
+        util::{
+            eq_by_key,
+            extensible
+        }
+    }
+};
+
+/// The cached data for a region. This type is an implementation detail. You're probably looking for `Region` instead.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(remote = "Self")]
+pub struct RegionData {
+    id: String,
+    #[serde(default)]
+    links: Vec<Link>,
+    name: String,
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extra")]
+    extra: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip)]
+    raw: Option<std::sync::Arc<serde_json::Value>>
+}
+
+/// Regions are the geographic regions in which game releases are sold.
+pub type Region = AnnotatedData<RegionData>;
+
+impl Resource for RegionData {
+    fn path(&self) -> String {
+        format!("/regions/{}", self.id)
+    }
+}
+
+impl Linked for RegionData {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+extensible!(RegionData);
+
+impl Region {
+    /// Returns a paginated list of all regions on speedrun.com.
+    pub fn list(client: impl Into<Client>) -> PaginatedList<RegionData> {
+        PaginatedList::new(client.into(), "/regions".into())
+    }
+
+    /// Returns the region with the given ID.
+    pub fn from_id(client: &Client, id: impl fmt::Display) -> Result<Region> {
+        Ok(client.annotate(
+            client.get(format!("/regions/{}", id))?
+        ))
+    }
+
+    /// Returns this region's API ID.
+    pub fn id(&self) -> &str {
+        &self.data.id
+    }
+}
+
+/// Displays the region name.
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.data.name.fmt(f)
+    }
+}
+
+eq_by_key!(Region, id);
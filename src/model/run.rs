@@ -2,29 +2,61 @@
 
 use {
     std::{
+        collections::BTreeMap,
         fmt,
         iter,
         time::Duration
     },
     chrono::prelude::*,
     reqwest::Url,
-    serde::Deserialize,
+    serde::{
+        Deserialize,
+        Deserializer,
+        Serialize,
+        Serializer,
+        de::Error as _,
+        ser::SerializeMap
+    },
     crate::{
+        FormatOptions,
         Result,
         client::{
             AnnotatedData,
             Client,
-            Link
+            Link,
+            Linked,
+            Resource
+        },
+        embed::Embeds,
+        format_duration,
+        model::{
+            game::{
+                Game,
+                Ruleset
+            },
+            guest::Guest,
+            user::User,
+            variable::{
+                Value,
+                Variable
+            }
+        },
+        paginated::{
+            Direction,
+            PaginatedList,
+            SortKey
         },
-        model::user::User,
         util::{
             DurationDef,
-            OptDurationDef
+            OptDurationDef,
+            ResultExt,
+            eq_by_key,
+            extensible
         }
     }
 };
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "snake_case")]
 #[serde(tag = "rel")]
 enum RunnerData {
@@ -32,19 +64,38 @@ enum RunnerData {
     Guest { name: String }
 }
 
+/// A reference to a player who participated in a run, returned by `Run::player_refs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlayerRef<'a> {
+    /// A registered user, identified by their API ID.
+    User(&'a str),
+    /// A guest, identified by their name.
+    Guest(&'a str)
+}
+
+impl<'a> From<&'a RunnerData> for PlayerRef<'a> {
+    fn from(data: &'a RunnerData) -> PlayerRef<'a> {
+        match data {
+            RunnerData::User { id } => PlayerRef::User(id),
+            RunnerData::Guest { name } => PlayerRef::Guest(name)
+        }
+    }
+}
+
 /// A player who participated in this run.
+#[allow(clippy::large_enum_variant)] // boxing the user would make matching on runners more cumbersome for little gain since runs only have a few runners
 pub enum Runner {
     /// A registered user.
     User(User),
-    /// A guest of whom only a name is documented.
-    Guest(String)
+    /// A guest without a registered account.
+    Guest(Guest)
 }
 
 impl Runner {
     fn new(client: &Client, data: &RunnerData) -> Result<Runner> {
         Ok(match *data {
             RunnerData::User { ref id } => { Runner::User(User::from_id(client, id)?) } //TODO
-            RunnerData::Guest { ref name } => Runner::Guest(name.clone())
+            RunnerData::Guest { ref name } => Runner::Guest(Guest::from_name(client, name)?)
         })
     }
 }
@@ -54,13 +105,39 @@ impl fmt::Display for Runner {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
             Runner::User(ref user) => user.fmt(f),
-            Runner::Guest(ref name) => name.fmt(f)
+            Runner::Guest(ref guest) => guest.fmt(f)
+        }
+    }
+}
+
+/// The different timing methods by which runs can be timed.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimingMethod {
+    /// The real duration of the run.
+    #[serde(rename = "realtime")]
+    RealTime,
+    /// The duration of the run when subtracting load times.
+    #[serde(rename = "realtime_noloads")]
+    RealTimeNoLoads,
+    /// The run time as measured by the game.
+    #[serde(rename = "ingame")]
+    InGame
+}
+
+impl TimingMethod {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            TimingMethod::RealTime => "realtime",
+            TimingMethod::RealTimeNoLoads => "realtime_noloads",
+            TimingMethod::InGame => "ingame"
         }
     }
 }
 
 /// The duration of a run in the different documented timing methods.
-#[derive(Debug, Deserialize, Clone)]
+///
+/// The durations are serialized in the same ISO 8601 format used by the API, see `format::format_iso8601`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Times {
     /// The primary time counted for the leaderboard. This will be the same as one of the other times.
     #[serde(with = "DurationDef")]
@@ -76,9 +153,40 @@ pub struct Times {
     pub ingame: Option<Duration>
 }
 
+impl Times {
+    /// Returns the duration of the run in the given timing method, if it was timed in that method.
+    pub fn get(&self, method: TimingMethod) -> Option<Duration> {
+        match method {
+            TimingMethod::RealTime => self.realtime,
+            TimingMethod::RealTimeNoLoads => self.realtime_noloads,
+            TimingMethod::InGame => self.ingame
+        }
+    }
+
+    /// Returns the timing method from which the primary time is taken, given the ruleset of the run's game.
+    ///
+    /// This is usually the game's default timing method. However, if the run wasn't timed in that method, the primary time is one of the other times, and the first timing method with a matching time is returned instead.
+    pub fn primary_method(&self, ruleset: &Ruleset) -> TimingMethod {
+        if self.get(ruleset.default_time) == Some(self.primary) { return ruleset.default_time; }
+        ruleset.run_times.iter()
+            .chain(&[TimingMethod::RealTime, TimingMethod::RealTimeNoLoads, TimingMethod::InGame])
+            .copied()
+            .find(|&method| self.get(method) == Some(self.primary))
+            .unwrap_or(ruleset.default_time)
+    }
+
+    /// Formats the primary time like speedrun.com does for the game with the given ruleset, i.e. with milliseconds only if the game shows them.
+    pub fn format_primary(&self, ruleset: &Ruleset) -> String {
+        format_duration(self.primary, FormatOptions {
+            show_milliseconds: ruleset.show_milliseconds,
+            ..FormatOptions::default()
+        })
+    }
+}
+
 /// The submission status of a run (verified, rejected, or new).
-#[derive(Debug, Deserialize, Clone)]
-#[serde(tag = "status", rename_all = "kebab-case")]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(remote = "Self", tag = "status", rename_all = "kebab-case")]
 pub enum RunStatus {
     /// The run has neither been verified nor rejected yet.
     New,
@@ -96,44 +204,263 @@ pub enum RunStatus {
         examiner: Option<String>,
         /// The reason why the run was rejected, given by the examiner.
         reason: String
+    },
+    /// A status which is not known to this library, e.g. because it was added to the API after this version was released. Contains the raw `status` value.
+    #[serde(skip)]
+    Unknown(String)
+}
+
+impl<'de> Deserialize<'de> for RunStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<RunStatus, D::Error> {
+        let status = serde_json::Value::deserialize(deserializer)?;
+        match status.get("status").and_then(serde_json::Value::as_str) {
+            // only unknown statuses fall back to `Unknown`, so errors in the fields of known statuses are still reported
+            Some("new") | Some("verified") | Some("rejected") | None => RunStatus::deserialize(status).map_err(D::Error::custom),
+            Some(status) => Ok(RunStatus::Unknown(status.to_owned()))
+        }
+    }
+}
+
+impl Serialize for RunStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            RunStatus::Unknown(status) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("status", status)?;
+                map.end()
+            }
+            _ => RunStatus::serialize(self, serializer)
+        }
     }
 }
 
 impl RunStatus {
     /// The user who verified or rejected this run. Returns `Ok(None)` if the run has neither been verified nor rejected, or if it's unknown who did so.
     pub fn examiner(&self, client: &Client) -> Result<Option<User>> {
-        Ok(match self {
+        self.examiner_id().map(|id| User::from_id(client, id)).transpose()
+    }
+
+    /// The API ID of the user who verified or rejected this run, without making an API request. Returns `None` if the run has neither been verified nor rejected, or if it's unknown who did so.
+    pub fn examiner_id(&self) -> Option<&str> {
+        match self {
             RunStatus::Verified { examiner: Some(id), .. }
-            | RunStatus::Rejected { examiner: Some(id), .. } => Some(User::from_id(client, id)?),
+            | RunStatus::Rejected { examiner: Some(id), .. } => Some(id),
             _ => None
-        })
+        }
+    }
+}
+
+/// The fields by which the results of `Run::search` and other lists of runs can be sorted, used with `RunsQuery::order_by` or `PaginatedList::order_by`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderBy {
+    /// Sort by game. This is the default.
+    Game,
+    /// Sort by category.
+    Category,
+    /// Sort by level.
+    Level,
+    /// Sort by platform.
+    Platform,
+    /// Sort by region.
+    Region,
+    /// Sort by whether the run was done on emulator.
+    Emulated,
+    /// Sort by the date the run was played.
+    Date,
+    /// Sort by the date the run was submitted.
+    Submitted,
+    /// Sort by submission status.
+    Status,
+    /// Sort by the date the run was verified.
+    VerifyDate
+}
+
+impl OrderBy {
+    fn as_str(&self) -> &'static str {
+        match self {
+            OrderBy::Game => "game",
+            OrderBy::Category => "category",
+            OrderBy::Level => "level",
+            OrderBy::Platform => "platform",
+            OrderBy::Region => "region",
+            OrderBy::Emulated => "emulated",
+            OrderBy::Date => "date",
+            OrderBy::Submitted => "submitted",
+            OrderBy::Status => "status",
+            OrderBy::VerifyDate => "verify-date"
+        }
+    }
+}
+
+impl SortKey for OrderBy {
+    type Item = RunData;
+
+    fn param_value(&self) -> &'static str {
+        self.as_str()
+    }
+}
+
+/// A filter for `RunsQuery::status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusFilter {
+    /// Only returns runs which have neither been verified nor rejected yet.
+    New,
+    /// Only returns verified runs.
+    Verified,
+    /// Only returns rejected runs.
+    Rejected
+}
+
+impl StatusFilter {
+    fn as_str(&self) -> &'static str {
+        match self {
+            StatusFilter::New => "new",
+            StatusFilter::Verified => "verified",
+            StatusFilter::Rejected => "rejected"
+        }
+    }
+}
+
+/// Search parameters for `Run::search`.
+///
+/// All filters are optional and can be combined. Filters taking an ID expect the API ID of the respective resource.
+#[derive(Debug, Default, Clone)]
+pub struct RunsQuery {
+    params: Vec<(&'static str, String)>
+}
+
+impl RunsQuery {
+    fn param(mut self, key: &'static str, value: impl ToString) -> RunsQuery {
+        self.params.retain(|&(existing_key, _)| existing_key != key);
+        self.params.push((key, value.to_string()));
+        self
+    }
+
+    /// Only returns runs done by the given user.
+    pub fn user(self, id: impl ToString) -> RunsQuery {
+        self.param("user", id)
+    }
+
+    /// Only returns runs done by the guest with the given name.
+    pub fn guest(self, name: impl ToString) -> RunsQuery {
+        self.param("guest", name)
     }
+
+    /// Only returns runs verified or rejected by the given user.
+    pub fn examiner(self, id: impl ToString) -> RunsQuery {
+        self.param("examiner", id)
+    }
+
+    /// Only returns runs done in the given game.
+    pub fn game(self, id: impl ToString) -> RunsQuery {
+        self.param("game", id)
+    }
+
+    /// Only returns runs done in the given level.
+    pub fn level(self, id: impl ToString) -> RunsQuery {
+        self.param("level", id)
+    }
+
+    /// Only returns runs done in the given category.
+    pub fn category(self, id: impl ToString) -> RunsQuery {
+        self.param("category", id)
+    }
+
+    /// Only returns runs done on the given platform.
+    pub fn platform(self, id: impl ToString) -> RunsQuery {
+        self.param("platform", id)
+    }
+
+    /// Only returns runs done in the given region.
+    pub fn region(self, id: impl ToString) -> RunsQuery {
+        self.param("region", id)
+    }
+
+    /// If `true`, only returns runs done on emulator. If `false`, only returns runs done on real devices.
+    pub fn emulated(self, emulated: bool) -> RunsQuery {
+        self.param("emulated", emulated)
+    }
+
+    /// Only returns runs with the given submission status.
+    pub fn status(self, status: StatusFilter) -> RunsQuery {
+        self.param("status", status.as_str())
+    }
+
+    /// Configures the sort order of the results.
+    pub fn order_by(self, order_by: OrderBy) -> RunsQuery {
+        self.param("orderby", order_by.as_str())
+    }
+
+    /// Configures the sort direction of the results.
+    pub fn direction(self, direction: Direction) -> RunsQuery {
+        self.param("direction", direction.as_str())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct System {
+    emulated: bool,
+    platform: Option<String>,
+    region: Option<String>
 }
 
-#[derive(Debug, Default, Deserialize, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 struct Videos {
     text: Option<String>,
     links: Option<Vec<Link>>
 }
 
 /// The cached data for a speedrun. This type is an implementation detail. You're probably looking for `Run` instead.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(remote = "Self")]
 pub struct RunData {
+    category: String,
+    comment: Option<String>,
     date: Option<NaiveDate>,
+    game: String,
     id: String,
+    level: Option<String>,
+    #[serde(default)]
+    links: Vec<Link>,
     players: Vec<RunnerData>,
+    splits: Option<Link>,
     status: RunStatus,
     submitted: Option<DateTime<Utc>>,
+    system: System,
     times: Times,
+    values: BTreeMap<String, String>,
     videos: Option<Videos>,
     #[serde(with = "url_serde")]
-    weblink: Url
+    weblink: Url,
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extra")]
+    extra: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip)]
+    raw: Option<std::sync::Arc<serde_json::Value>>
 }
 
 /// The type representing a speedrun.
 pub type Run = AnnotatedData<RunData>;
 
+impl Resource for RunData {
+    fn path(&self) -> String {
+        format!("/runs/{}", self.id)
+    }
+}
+
+impl Linked for RunData {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+extensible!(RunData);
+
 impl Run {
+    /// Returns a paginated list of all runs matching the given search parameters.
+    pub fn search(client: impl Into<Client>, query: RunsQuery) -> PaginatedList<RunData> {
+        PaginatedList::with_query(client.into(), "/runs".into(), query.params.into_iter().map(|(key, value)| (key.to_owned(), value)).collect())
+    }
+
     /// Returns the run with the given ID.
     pub fn from_id(client: &Client, id: impl fmt::Display) -> Result<Run> {
         Ok(client.annotate(
@@ -141,21 +468,82 @@ impl Run {
         ))
     }
 
+    /// Returns the run with the given ID, requesting the given resources to be embedded in the response.
+    ///
+    /// See the `embed` module for details.
+    pub fn from_id_with_embeds(client: &Client, id: impl fmt::Display, embeds: &Embeds) -> Result<Run> {
+        Ok(client.annotate(
+            client.get_query(format!("/runs/{}", id), embeds.query())?
+        ))
+    }
+
     /// Returns this run's API ID.
     pub fn id(&self) -> &str {
         &self.data.id
     }
 
+    /// Returns the API ID of this run's category.
+    pub fn category_id(&self) -> &str {
+        &self.data.category
+    }
+
+    /// The comment the runner gave when submitting the run, if any.
+    pub fn comment(&self) -> Option<&str> {
+        self.data.comment.as_deref()
+    }
+
     /// The date on which the run was played, if known. Submitted by the runner.
     pub fn date(&self) -> Option<NaiveDate> {
         self.data.date
     }
 
+    /// Returns `true` if this run was done on an emulator.
+    pub fn emulated(&self) -> bool {
+        self.data.system.emulated
+    }
+
     /// The user who verified or rejected this run. Returns `Ok(None)` if the run has neither been verified nor rejected, of if it's unknown who did so.
     pub fn examiner(&self, client: &Client) -> Result<Option<User>> {
         self.status().examiner(client)
     }
 
+    /// The API ID of the user who verified or rejected this run, without making an API request. Returns `None` if the run has neither been verified nor rejected, or if it's unknown who did so.
+    pub fn examiner_id(&self) -> Option<&str> {
+        self.status().examiner_id()
+    }
+
+    /// Returns the game of this run.
+    pub fn game(&self) -> Result<Game> {
+        Game::from_id(&self.client, &self.data.game)
+    }
+
+    /// Returns the API ID of this run's game.
+    pub fn game_id(&self) -> &str {
+        &self.data.game
+    }
+
+    /// For IL runs, returns the API ID of the run's level.
+    pub fn level_id(&self) -> Option<&str> {
+        self.data.level.as_deref()
+    }
+
+    /// Returns the API ID of the platform this run was done on, if any.
+    pub fn platform_id(&self) -> Option<&str> {
+        self.data.system.platform.as_deref()
+    }
+
+    /// Returns references to the players who participated in this run, without making any API requests.
+    ///
+    /// See also: the `runners` method, which requests the users' and guests' data.
+    pub fn player_refs(&self) -> impl Iterator<Item = PlayerRef<'_>> {
+        self.data.players.iter().map(PlayerRef::from)
+    }
+
+    /// Returns the API ID of the region this run was done in, if any.
+    pub fn region_id(&self) -> Option<&str> {
+        self.data.system.region.as_deref()
+    }
+
     /// Returns the list of players who participated in this run.
     pub fn runners(&self) -> Result<Vec<Runner>> {
         self.data.players.iter()
@@ -163,6 +551,11 @@ impl Run {
             .collect()
     }
 
+    /// Returns the link to this run's splits, if any. This usually points to the [splits.io](https://splits.io/) API.
+    pub fn splits_uri(&self) -> Option<&Url> {
+        self.data.splits.as_ref().map(|link| &link.uri)
+    }
+
     /// The current submission status of this run (verified, rejected, or new).
     pub fn status(&self) -> &RunStatus {
         &self.data.status
@@ -190,10 +583,37 @@ impl Run {
         self.data.videos.as_ref().and_then(|videos| videos.text.as_ref()).map(String::as_str)
     }
 
+    /// Returns the variable/value pairs of this run, as a map from variable IDs to value IDs.
+    pub fn value_ids(&self) -> &BTreeMap<String, String> {
+        &self.data.values
+    }
+
+    /// Returns the variable/value pairs of this run, resolved to the variable and value objects.
+    ///
+    /// The variables are looked up in the list of variables for the run's category, which is cached across runs. Variables not found there are requested individually.
+    pub fn values(&self) -> Result<Vec<(Variable, Value)>> {
+        const OPERATION: &str = "Run::values";
+        if self.data.values.is_empty() { return Ok(Vec::default()); }
+        let category_variables = self.client.get_annotated_collection::<_, Vec<Variable>>(format!("/categories/{}/variables", self.data.category)).context(OPERATION)?;
+        self.data.values.iter()
+            .filter_map(|(variable_id, value_id)| {
+                let variable = match category_variables.iter().find(|variable| variable.id() == variable_id) {
+                    Some(variable) => variable.clone(),
+                    None => match Variable::from_id(&self.client, variable_id).context(OPERATION) {
+                        Ok(variable) => variable,
+                        Err(e) => { return Some(Err(e)); }
+                    }
+                };
+                // values which have since been deleted from the variable are skipped
+                variable.value(value_id).map(|value| Ok((variable, value)))
+            })
+            .collect()
+    }
+
     /// Returns the video link given in the submission, if any, followed by any recognized video links in the description.
     ///
     /// Only some video websites are recognized when in the description, see [the API docs](https://github.com/speedruncomorg/api/blob/master/version1/runs.md) for details.
-    pub fn videos<'a>(&'a self) -> Box<(dyn Iterator<Item = &Url> + 'a)> {
+    pub fn videos<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Url> + 'a> {
         if let Some(links) = self.data.videos.as_ref().and_then(|videos| videos.links.as_ref()) {
             Box::new(links.iter().map(|link| &link.uri))
         } else {
@@ -206,3 +626,5 @@ impl Run {
         &self.data.weblink
     }
 }
+
+eq_by_key!(Run, id);
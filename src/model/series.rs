@@ -0,0 +1,140 @@
+//! Series are collections of related games, like the games of a franchise
+
+use {
+    std::fmt,
+    reqwest::Url,
+    serde::{
+        Deserialize,
+        Serialize
+    },
+    crate::{
+        Result,
+        client::{
+            AnnotatedData,
+            Client,
+            Link,
+            Linked,
+            Resource
+        },
+        model::game::{
+            GameData,
+            Names
+        },
+        paginated::{
+            PaginatedList,
+            SortKey
+        },
+        util::{
+            eq_by_key,
+            extensible
+        }
+    }
+};
+
+/// The fields by which the list of series can be sorted, used with `PaginatedList::order_by`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderBy {
+    /// Sort by international name. This is the default.
+    NameInternational,
+    /// Sort by Japanese name.
+    NameJapanese,
+    /// Sort by abbreviation.
+    Abbreviation,
+    /// Sort by the date the series was added to speedrun.com.
+    Created
+}
+
+impl SortKey for OrderBy {
+    type Item = SeriesData;
+
+    fn param_value(&self) -> &'static str {
+        match self {
+            OrderBy::NameInternational => "name.int",
+            OrderBy::NameJapanese => "name.jap",
+            OrderBy::Abbreviation => "abbreviation",
+            OrderBy::Created => "created"
+        }
+    }
+}
+
+/// The cached data for a series. This type is an implementation detail. You're probably looking for `Series` instead.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(remote = "Self")]
+pub struct SeriesData {
+    id: String,
+    #[serde(default)]
+    links: Vec<Link>,
+    abbreviation: String,
+    names: Names,
+    #[serde(with = "url_serde")]
+    weblink: Url,
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extra")]
+    extra: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip)]
+    raw: Option<std::sync::Arc<serde_json::Value>>
+}
+
+/// Series are collections of related games, like the games of a franchise.
+pub type Series = AnnotatedData<SeriesData>;
+
+impl Resource for SeriesData {
+    fn path(&self) -> String {
+        format!("/series/{}", self.id)
+    }
+}
+
+impl Linked for SeriesData {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+extensible!(SeriesData);
+
+impl Series {
+    /// Returns a paginated list of all series on speedrun.com.
+    pub fn list(client: impl Into<Client>) -> PaginatedList<SeriesData> {
+        PaginatedList::new(client.into(), "/series".into())
+    }
+
+    /// Returns the series with the given ID or abbreviation.
+    pub fn from_id(client: &Client, id: impl fmt::Display) -> Result<Series> {
+        Ok(client.annotate(
+            client.get(format!("/series/{}", id))?
+        ))
+    }
+
+    /// Returns this series' abbreviation, as used in its URL on speedrun.com.
+    pub fn abbreviation(&self) -> &str {
+        &self.data.abbreviation
+    }
+
+    /// Returns a paginated list of all games in this series.
+    pub fn games(&self) -> PaginatedList<GameData> {
+        PaginatedList::new(self.client.clone(), format!("/series/{}/games", self.id()))
+    }
+
+    /// Returns this series' API ID.
+    pub fn id(&self) -> &str {
+        &self.data.id
+    }
+
+    /// Returns the different names registered for this series.
+    pub fn names(&self) -> &Names {
+        &self.data.names
+    }
+
+    /// Returns the link to this series' page intended for humans.
+    pub fn weblink(&self) -> &Url {
+        &self.data.weblink
+    }
+}
+
+/// Displays the series' English name, or its Japanese name if preferred by the client.
+impl fmt::Display for Series {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.data.names.preferred(self.client.name_preference()).fmt(f)
+    }
+}
+
+eq_by_key!(Series, id);
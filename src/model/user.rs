@@ -1,21 +1,57 @@
 //! Users are the individuals who have registered an account on speedrun.com
 
 use {
-    std::fmt,
+    std::{
+        collections::{
+            BTreeMap,
+            BTreeSet
+        },
+        fmt
+    },
     chrono::prelude::*,
-    serde::Deserialize,
+    itertools::Itertools,
+    reqwest::Url,
+    serde::{
+        Deserialize,
+        Serialize
+    },
     crate::{
         Result,
         client::{
             AnnotatedData,
-            Client
+            Client,
+            Link,
+            Linked,
+            NamePreference,
+            Resource
+        },
+        model::{
+            game::{
+                Game,
+                GameData,
+                GamesQuery
+            },
+            leaderboard::{
+                LeaderboardEntry,
+                LeaderboardEntryData
+            },
+            variable::Variable
         },
-        paginated::PaginatedList
+        paginated::{
+            Direction,
+            PaginatedList,
+            SortKey
+        },
+        util::{
+            ResultExt,
+            eq_by_key,
+            extensible
+        }
     }
 };
 
 /// The different names a user has registered.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Names {
     /// The user's international, or main, username.
     pub international: String,
@@ -23,23 +59,279 @@ pub struct Names {
     pub japanese: Option<String>,
 }
 
+impl Names {
+    /// Returns the Japanese name if it's preferred and registered, and the international name otherwise.
+    pub fn preferred(&self, preference: NamePreference) -> &str {
+        preference.choose(&self.international, self.japanese.as_deref())
+    }
+}
+
+/// A country, or a region within a country, as used in `Location`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Area {
+    /// The area's code. For countries, this is the ISO 3166-1 alpha-2 code in lowercase, e.g. `de`. For regions, it's the country code followed by a `/` and a region code, e.g. `de/bw`.
+    pub code: String,
+    /// The area's names.
+    pub names: Names
+}
+
+/// The location a user has specified in their profile.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Location {
+    /// The user's country.
+    pub country: Area,
+    /// The user's region within the country, if specified.
+    pub region: Option<Area>
+}
+
+/// A color used in a `NameStyle`, with variants for the site's light and dark themes.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Color {
+    /// The color used on the light theme, as a hex code like `#EE2222`.
+    pub light: String,
+    /// The color used on the dark theme, as a hex code like `#EE4444`.
+    pub dark: String
+}
+
+/// How a user's name is colored on the site.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(tag = "style", rename_all = "kebab-case")]
+pub enum NameStyle {
+    /// The name is displayed in a single color.
+    Solid {
+        /// The color of the name.
+        color: Color
+    },
+    /// The name is displayed in a horizontal color gradient.
+    #[serde(rename_all = "kebab-case")]
+    Gradient {
+        /// The color at the start of the name.
+        color_from: Color,
+        /// The color at the end of the name.
+        color_to: Color
+    }
+}
+
+/// A user's site-wide role, returned by `User::role`.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Role {
+    /// The user has been banned from the site.
+    Banned,
+    /// A regular user.
+    User,
+    /// A trusted user.
+    Trusted,
+    /// A site moderator.
+    Moderator,
+    /// A site administrator.
+    Admin,
+    /// A site developer.
+    Programmer,
+    /// A role which is not known to this library, e.g. because it was added to the API after this version was released.
+    #[serde(other)]
+    Unknown
+}
+
+/// A leaderboard on which at least one of two users has a personal best, along with both users' personal bests. Returned by `User::compare_pbs`.
+#[derive(Debug, Clone)]
+pub struct PbComparison {
+    /// The API ID of the leaderboard's category.
+    pub category_id: String,
+    /// For IL leaderboards, the API ID of the leaderboard's level.
+    pub level_id: Option<String>,
+    /// The subcategory values of the leaderboard, as a map from variable IDs to value IDs.
+    pub values: BTreeMap<String, String>,
+    /// The personal best of the user on which `compare_pbs` was called, if any.
+    pub own: Option<LeaderboardEntry>,
+    /// The personal best of the other user, if any.
+    pub other: Option<LeaderboardEntry>
+}
+
+impl PbComparison {
+    /// Returns the difference between the primary times of the two personal bests, if both users have one. The difference is negative if `own` is faster.
+    pub fn delta(&self) -> Option<chrono::Duration> {
+        let own = chrono::Duration::from_std(self.own.as_ref()?.run.time()).ok()?;
+        let other = chrono::Duration::from_std(self.other.as_ref()?.run.time()).ok()?;
+        Some(own - other)
+    }
+}
+
+/// The fields by which the results of `User::search` and other lists of users can be sorted, used with `UsersQuery::order_by` or `PaginatedList::order_by`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderBy {
+    /// Sort by international username. This is the default.
+    NameInternational,
+    /// Sort by Japanese username.
+    NameJapanese,
+    /// Sort by signup date.
+    Signup,
+    /// Sort by role.
+    Role
+}
+
+impl OrderBy {
+    fn as_str(&self) -> &'static str {
+        match self {
+            OrderBy::NameInternational => "name.int",
+            OrderBy::NameJapanese => "name.jap",
+            OrderBy::Signup => "signup",
+            OrderBy::Role => "role"
+        }
+    }
+}
+
+impl SortKey for OrderBy {
+    type Item = UserData;
+
+    fn param_value(&self) -> &'static str {
+        self.as_str()
+    }
+}
+
+/// Search parameters for `User::search`.
+///
+/// All filters are optional and can be combined.
+#[derive(Debug, Default, Clone)]
+pub struct UsersQuery {
+    params: Vec<(&'static str, String)>
+}
+
+impl UsersQuery {
+    fn param(mut self, key: &'static str, value: impl ToString) -> UsersQuery {
+        self.params.retain(|&(existing_key, _)| existing_key != key);
+        self.params.push((key, value.to_string()));
+        self
+    }
+
+    /// Only returns the user whose name or any of whose linked account names matches the given string exactly (case-insensitive). If this is used, all other filters are ignored by the API.
+    pub fn lookup(self, name: impl ToString) -> UsersQuery {
+        self.param("lookup", name)
+    }
+
+    /// Only returns users whose name contains the given string (case-insensitive).
+    pub fn name(self, name: impl ToString) -> UsersQuery {
+        self.param("name", name)
+    }
+
+    /// Only returns users with the given Twitch username.
+    pub fn twitch(self, name: impl ToString) -> UsersQuery {
+        self.param("twitch", name)
+    }
+
+    /// Only returns users with the given Hitbox username.
+    pub fn hitbox(self, name: impl ToString) -> UsersQuery {
+        self.param("hitbox", name)
+    }
+
+    /// Only returns users with the given Twitter username.
+    pub fn twitter(self, name: impl ToString) -> UsersQuery {
+        self.param("twitter", name)
+    }
+
+    /// Only returns users with the given SpeedRunsLive username.
+    pub fn speedrunslive(self, name: impl ToString) -> UsersQuery {
+        self.param("speedrunslive", name)
+    }
+
+    /// Configures the sort order of the results.
+    pub fn order_by(self, order_by: OrderBy) -> UsersQuery {
+        self.param("orderby", order_by.as_str())
+    }
+
+    /// Configures the sort direction of the results.
+    pub fn direction(self, direction: Direction) -> UsersQuery {
+        self.param("direction", direction.as_str())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct UserAsset {
+    #[serde(default, with = "url_serde")]
+    uri: Option<Url>
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+struct UserAssets {
+    #[serde(default)]
+    icon: Option<UserAsset>,
+    #[serde(default, rename = "supporterIcon")]
+    supporter_icon: Option<UserAsset>,
+    #[serde(default)]
+    image: Option<UserAsset>
+}
+
+impl UserAssets {
+    fn uri(asset: &Option<UserAsset>) -> Option<&Url> {
+        asset.as_ref().and_then(|asset| asset.uri.as_ref())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct SocialLink {
+    #[serde(with = "url_serde")]
+    uri: Url
+}
+
 /// The cached data for a user. This type is an implementation detail. You're probably looking for `User` instead.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(remote = "Self")]
 pub struct UserData {
     id: String,
+    #[serde(default)]
+    links: Vec<Link>,
+    #[serde(default)]
+    assets: UserAssets,
+    hitbox: Option<SocialLink>,
+    location: Option<Location>,
     names: Names,
-    signup: Option<DateTime<Utc>>
+    #[serde(rename = "name-style")]
+    name_style: NameStyle,
+    pronouns: Option<String>,
+    role: Role,
+    signup: Option<DateTime<Utc>>,
+    speedrunslive: Option<SocialLink>,
+    #[serde(default, rename = "supporterAnimation")]
+    supporter_animation: bool,
+    twitch: Option<SocialLink>,
+    twitter: Option<SocialLink>,
+    #[serde(with = "url_serde")]
+    weblink: Url,
+    youtube: Option<SocialLink>,
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extra")]
+    extra: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip)]
+    raw: Option<std::sync::Arc<serde_json::Value>>
 }
 
 /// Users are the individuals who have registered an account on speedrun.com.
 pub type User = AnnotatedData<UserData>;
 
+impl Resource for UserData {
+    fn path(&self) -> String {
+        format!("/users/{}", self.id)
+    }
+}
+
+impl Linked for UserData {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+extensible!(UserData);
+
 impl User {
     /// Returns a paginated list of all games on speedrun.com.
     pub fn list(client: impl Into<Client>) -> PaginatedList<UserData> {
         PaginatedList::new(client.into(), "/users".into())
     }
 
+    /// Returns a paginated list of all users matching the given search parameters.
+    pub fn search(client: impl Into<Client>, query: UsersQuery) -> PaginatedList<UserData> {
+        PaginatedList::with_query(client.into(), "/users".into(), query.params.into_iter().map(|(key, value)| (key.to_owned(), value)).collect())
+    }
+
     /// Returns the user with the given ID or username.
     pub fn from_id(client: &Client, id: impl fmt::Display) -> Result<User> {
         Ok(client.annotate(
@@ -47,15 +339,147 @@ impl User {
         ))
     }
 
+    /// Calls `from_id` for each of the given IDs or usernames, skipping duplicates, and returns the users in order.
+    ///
+    /// This is a convenience wrapper, not a bulk request: the API has no endpoint for requesting multiple users at once, so each user which isn't already in the client's cache (e.g. because it was embedded in a leaderboard response) costs one request.
+    pub fn from_ids(client: &Client, ids: &[impl AsRef<str>]) -> Result<Vec<User>> {
+        ids.iter()
+            .map(AsRef::as_ref)
+            .unique()
+            .map(|id| User::from_id(client, id))
+            .collect()
+    }
+
+    /// Lines up this user's personal bests in the given game with those of another user, one entry per leaderboard on which at least one of them has a personal best.
+    ///
+    /// Leaderboards are distinguished by category, level, and the values of subcategory variables. The entries are sorted by category ID, level ID, and values.
+    pub fn compare_pbs(&self, other: &User, game: &Game) -> Result<Vec<PbComparison>> {
+        const OPERATION: &str = "User::compare_pbs";
+        let subcategory_ids = self.client.get_annotated_collection::<_, Vec<Variable>>(format!("/games/{}/variables", game.id())).context(OPERATION)?.into_iter()
+            .filter(|variable| variable.is_subcategory())
+            .map(|variable| variable.id().to_owned())
+            .collect::<BTreeSet<_>>();
+        let mut comparisons = BTreeMap::<_, (Option<LeaderboardEntry>, Option<LeaderboardEntry>)>::default();
+        for (user, is_own) in [(self, true), (other, false)] {
+            for pb in user.personal_bests(game).context(OPERATION)? {
+                let key = (
+                    pb.run.category_id().to_owned(),
+                    pb.run.level_id().map(str::to_owned),
+                    pb.run.value_ids().iter()
+                        .filter(|(variable_id, _)| subcategory_ids.contains(*variable_id))
+                        .map(|(variable_id, value_id)| (variable_id.clone(), value_id.clone()))
+                        .collect::<BTreeMap<_, _>>()
+                );
+                let entry = comparisons.entry(key).or_default();
+                if is_own { entry.0 = Some(pb) } else { entry.1 = Some(pb) }
+            }
+        }
+        Ok(comparisons.into_iter()
+            .map(|((category_id, level_id, values), (own, other))| PbComparison { category_id, level_id, values, own, other })
+            .collect())
+    }
+
+    fn personal_bests(&self, game: &Game) -> Result<Vec<LeaderboardEntry>> {
+        Ok(self.client.get_query::<_, _, _, _, Vec<LeaderboardEntryData>>(format!("/users/{}/personal-bests", self.id()), vec![("game", game.id())])?
+            .into_iter()
+            .map(|pb| LeaderboardEntry {
+                place: pb.place,
+                run: self.client.annotate(pb.run)
+            })
+            .collect())
+    }
+
+    /// Returns this user's API ID.
+    pub fn id(&self) -> &str {
+        &self.data.id
+    }
+
+    /// Returns the link to this user's Hitbox channel, if linked.
+    pub fn hitbox(&self) -> Option<&Url> {
+        self.data.hitbox.as_ref().map(|link| &link.uri)
+    }
+
+    /// Returns the URL of this user's profile icon, if they have uploaded one.
+    pub fn icon(&self) -> Option<&Url> {
+        UserAssets::uri(&self.data.assets.icon)
+    }
+
+    /// Returns the URL of this user's profile image, if they have uploaded one.
+    pub fn image(&self) -> Option<&Url> {
+        UserAssets::uri(&self.data.assets.image)
+    }
+
+    /// Returns the location this user has specified in their profile, if any.
+    pub fn location(&self) -> Option<&Location> {
+        self.data.location.as_ref()
+    }
+
+    /// Returns a paginated list of the games this user moderates.
+    pub fn moderated_games(&self) -> PaginatedList<GameData> {
+        Game::search(&self.client, GamesQuery::default().moderator(self.id()))
+    }
+
+    /// Returns how this user's name is colored on the site.
+    pub fn name_style(&self) -> &NameStyle {
+        &self.data.name_style
+    }
+
+    /// Returns the pronouns this user has specified in their profile, if any.
+    pub fn pronouns(&self) -> Option<&str> {
+        self.data.pronouns.as_deref()
+    }
+
+    /// Returns this user's site-wide role.
+    pub fn role(&self) -> Role {
+        self.data.role
+    }
+
     /// Returns the timestamp when this user account was created. `None` for old user accounts.
     pub fn signup(&self) -> &Option<DateTime<Utc>> {
         &self.data.signup
     }
+
+    /// Returns the link to this user's SpeedRunsLive profile, if linked.
+    pub fn speedrunslive(&self) -> Option<&Url> {
+        self.data.speedrunslive.as_ref().map(|link| &link.uri)
+    }
+
+    /// Returns whether this user's name is displayed with the animation for site supporters.
+    pub fn supporter_animation(&self) -> bool {
+        self.data.supporter_animation
+    }
+
+    /// Returns the URL of this user's site supporter icon, if they have one.
+    pub fn supporter_icon(&self) -> Option<&Url> {
+        UserAssets::uri(&self.data.assets.supporter_icon)
+    }
+
+    /// Returns the link to this user's Twitch channel, if linked.
+    pub fn twitch(&self) -> Option<&Url> {
+        self.data.twitch.as_ref().map(|link| &link.uri)
+    }
+
+    /// Returns the link to this user's Twitter profile, if linked.
+    pub fn twitter(&self) -> Option<&Url> {
+        self.data.twitter.as_ref().map(|link| &link.uri)
+    }
+
+    /// Returns the URL to this user's profile on speedrun.com.
+    pub fn weblink(&self) -> &Url {
+        &self.data.weblink
+    }
+
+    /// Returns the link to this user's YouTube channel, if linked.
+    pub fn youtube(&self) -> Option<&Url> {
+        self.data.youtube.as_ref().map(|link| &link.uri)
+    }
 }
 
-/// Displays the users's international username.
+/// Displays the users's international username, or their Japanese name if preferred by the client.
 impl fmt::Display for User {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.data.names.international.fmt(f)
+        self.data.names.preferred(self.client.name_preference()).fmt(f)
     }
 }
+
+eq_by_key!(User, id);
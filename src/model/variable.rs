@@ -16,20 +16,33 @@ use {
         Serialize
     },
     crate::{
+        Error,
         Result,
         client::{
             AnnotatedData,
-            Client
+            Client,
+            Link,
+            Linked,
+            Resource
+        },
+        model::{
+            category::Category,
+            run::Run
+        },
+        util::{
+            deserialize_flags,
+            eq_by_key,
+            extensible
         }
     }
 };
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 struct ValueData {
     label: String,
     rules: Option<String>,
-    //#[serde(default)]
-    //flags: HashMap<String, bool> //TODO apparently this sometimes has nulls in it? Need to figure out how to handle those
+    #[serde(default, deserialize_with = "deserialize_flags")]
+    flags: HashMap<String, bool>
 }
 
 /// A possible value of a variable.
@@ -50,36 +63,97 @@ impl Value {
         &self.inner.label
     }
 
-    /// If this is a subcategory, returns the subcategory's rules.
+    /// If this is the value of a subcategory variable (see `Variable::is_subcategory`), returns the subcategory's rules.
     pub fn rules(&self) -> Option<&str> {
         self.inner.rules.as_ref().map(|rules_buf| &rules_buf[..])
     }
 
-    /*
     /// If this is a subcategory, returns whether or not it is considered miscellaneous, i.e. hidden behind a “more” button by default.
     pub fn is_misc(&self) -> Option<bool> {
-        self.inner.flags.get("miscellaneous").cloned()
+        self.inner.flags.get("miscellaneous").copied()
     }
-    */
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 struct ValuesData {
     values: HashMap<String, ValueData>,
     default: Option<String>
 }
 
+/// Where a variable applies, returned by `Variable::scope`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Scope {
+    /// The variable applies to full-game runs as well as all ILs.
+    Global,
+    /// The variable only applies to full-game runs.
+    FullGame,
+    /// The variable applies to ILs of all levels, but not to full-game runs.
+    AllLevels,
+    /// The variable only applies to ILs of a single level.
+    SingleLevel {
+        /// The API ID of the level.
+        level: String
+    }
+}
+
+impl Scope {
+    /// Returns `true` if a variable with this scope applies to full-game runs.
+    pub fn applies_to_full_game(&self) -> bool {
+        match self {
+            Scope::Global | Scope::FullGame => true,
+            Scope::AllLevels | Scope::SingleLevel { .. } => false
+        }
+    }
+
+    /// Returns `true` if a variable with this scope applies to ILs of the level with the given API ID.
+    pub fn applies_to_level(&self, level_id: &str) -> bool {
+        match self {
+            Scope::Global | Scope::AllLevels => true,
+            Scope::FullGame => false,
+            Scope::SingleLevel { level } => level == level_id
+        }
+    }
+}
+
 /// The cached data for a variable. This type is an implementation detail. You're probably looking for `Variable` instead.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(remote = "Self", rename_all = "kebab-case")]
 pub struct VariableData {
+    category: Option<String>,
     id: String,
+    #[serde(default)]
+    links: Vec<Link>,
+    is_subcategory: bool,
+    mandatory: bool,
     name: String,
-    values: ValuesData
+    obsoletes: bool,
+    scope: Scope,
+    user_defined: bool,
+    values: ValuesData,
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extra")]
+    extra: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip)]
+    raw: Option<std::sync::Arc<serde_json::Value>>
 }
 
 /// Variables are custom criteria to distinguish between runs done in the same category or level.
 pub type Variable = AnnotatedData<VariableData>;
 
+impl Resource for VariableData {
+    fn path(&self) -> String {
+        format!("/variables/{}", self.id)
+    }
+}
+
+impl Linked for VariableData {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+extensible!(VariableData);
+
 impl Variable {
     /// Returns the variable with the given ID.
     pub fn from_id(client: &Client, id: impl fmt::Display) -> Result<Variable> {
@@ -88,11 +162,54 @@ impl Variable {
         ))
     }
 
+    /// Returns the category this variable is restricted to. Returns `Ok(None)` if the variable applies to all categories of its game.
+    pub fn category(&self) -> Result<Option<Category>> {
+        self.category_id().map(|category_id| Category::from_id(&self.client, category_id)).transpose()
+    }
+
+    /// Returns the API ID of the category this variable is restricted to, or `None` if the variable applies to all categories of its game.
+    pub fn category_id(&self) -> Option<&str> {
+        self.data.category.as_deref()
+    }
+
     /// Returns this variable's API ID.
     pub fn id(&self) -> &str {
         &self.data.id
     }
 
+    /// Returns `true` if this variable defines subcategories, i.e. each of its values is displayed as a separate leaderboard on speedrun.com.
+    pub fn is_subcategory(&self) -> bool {
+        self.data.is_subcategory
+    }
+
+    /// Returns `true` if a value for this variable must be specified when submitting a run.
+    pub fn is_mandatory(&self) -> bool {
+        self.data.mandatory
+    }
+
+    /// Returns `true` if runners can enter arbitrary values for this variable when submitting a run, instead of only choosing from the predefined values.
+    pub fn is_user_defined(&self) -> bool {
+        self.data.user_defined
+    }
+
+    /// Returns the API ID of the level this variable is restricted to, or `None` if its scope isn't a single level.
+    pub fn level_id(&self) -> Option<&str> {
+        match &self.data.scope {
+            Scope::SingleLevel { level } => Some(level),
+            _ => None
+        }
+    }
+
+    /// Returns `true` if this variable is taken into account when determining whether a run obsoletes another, i.e. runs with different values for this variable are ranked separately.
+    pub fn obsoletes(&self) -> bool {
+        self.data.obsoletes
+    }
+
+    /// Returns where this variable applies.
+    pub fn scope(&self) -> &Scope {
+        &self.data.scope
+    }
+
     /// Returns the list of possible values this variable can be.
     pub fn values(&self) -> Vec<Value> {
         self.data.values.values.iter()
@@ -103,6 +220,14 @@ impl Variable {
             .collect()
     }
 
+    /// Returns the possible value of this variable with the given ID, if any.
+    pub fn value(&self, id: &str) -> Option<Value> {
+        self.data.values.values.get(id).map(|value_data| Value {
+            id: id.to_owned(),
+            inner: value_data.clone()
+        })
+    }
+
     /// Returns the default value of this variable, if defined.
     pub fn default_value(&self) -> Option<Value> {
         self.data.values.default.as_ref().map(|default_id| Value {
@@ -119,10 +244,75 @@ impl fmt::Display for Variable {
     }
 }
 
+eq_by_key!(Variable, id);
+
 /// This type is used to filter leaderboards by variable/value pairs via the `Category::leaderboard_filtered` method.
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct Filter(BTreeMap<String, String>);
 
+impl Filter {
+    /// Constructs a filter from pairs of variable names and value labels, looking up the category's variables to find the corresponding IDs. Names and labels are compared case-insensitively.
+    ///
+    /// # Errors
+    ///
+    /// In addition to request errors, returns `Error::UnknownVariableName` or `Error::UnknownValueLabel` if a name or label doesn't match any of the category's variables or their values.
+    pub fn from_labels(category: &Category, labels: &[(impl AsRef<str>, impl AsRef<str>)]) -> Result<Filter> {
+        let variables = category.variables::<Vec<_>>()?;
+        let mut builder = Filter::builder();
+        for (variable_name, value_label) in labels {
+            let variable_name = variable_name.as_ref().to_lowercase();
+            let variable = variables.iter()
+                .find(|variable| variable.data.name.to_lowercase() == variable_name)
+                .ok_or_else(|| Error::UnknownVariableName(variable_name.clone()))?;
+            let value_label = value_label.as_ref().to_lowercase();
+            let value = variable.values().into_iter()
+                .find(|value| value.label().to_lowercase() == value_label)
+                .ok_or_else(|| Error::UnknownValueLabel {
+                    variable: variable.id().to_owned(),
+                    label: value_label.clone()
+                })?;
+            builder = builder.set(variable, &value)?;
+        }
+        Ok(builder.build())
+    }
+
+    /// Checks that every variable in this filter applies to the given category, and that each value is one of its variable's possible values.
+    ///
+    /// # Errors
+    ///
+    /// In addition to request errors, returns `Error::VariableNotInCategory` or `Error::ValueNotInVariable` for the first invalid variable/value pair.
+    pub fn validate(&self, category: &Category) -> Result<()> {
+        let variables = category.variables::<Vec<_>>()?;
+        for (key, value_id) in &self.0 {
+            let variable_id = key.strip_prefix("var-").unwrap_or(key);
+            let variable = variables.iter()
+                .find(|variable| variable.id() == variable_id)
+                .ok_or_else(|| Error::VariableNotInCategory {
+                    variable: variable_id.to_owned(),
+                    category: category.id().to_owned()
+                })?;
+            if variable.value(value_id).is_none() {
+                return Err(Error::ValueNotInVariable {
+                    variable: variable_id.to_owned(),
+                    value: value_id.clone()
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the given run has each of this filter's values. An empty filter matches every run.
+    pub fn matches(&self, run: &Run) -> bool {
+        let value_ids = run.value_ids();
+        self.0.iter().all(|(key, value_id)| value_ids.get(key.strip_prefix("var-").unwrap_or(key)) == Some(value_id))
+    }
+
+    /// Returns a builder for constructing a filter from `Variable` and `Value` objects, ensuring that each value belongs to its variable.
+    pub fn builder() -> FilterBuilder {
+        FilterBuilder::default()
+    }
+}
+
 impl<K: fmt::Display, V: ToString> From<BTreeMap<K, V>> for Filter {
     fn from(map: BTreeMap<K, V>) -> Filter {
         Filter(map.into_iter().map(|(var_id, value_id)| (format!("var-{}", var_id), value_id.to_string())).collect())
@@ -149,3 +339,30 @@ impl<'a> IntoIterator for &'a Filter {
         self.0.iter()
     }
 }
+
+/// A builder for `Filter`s which checks that each value is a possible value of its variable. Created using `Filter::builder`.
+#[derive(Debug, Default, Clone)]
+pub struct FilterBuilder(BTreeMap<String, String>);
+
+impl FilterBuilder {
+    /// Adds the given variable/value pair to the filter, replacing any value previously set for that variable.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ValueNotInVariable` if `value` is not one of the possible values of `variable`.
+    pub fn set(mut self, variable: &Variable, value: &Value) -> Result<FilterBuilder> {
+        if variable.value(value.id()).is_none() {
+            return Err(Error::ValueNotInVariable {
+                variable: variable.id().to_owned(),
+                value: value.id().to_owned()
+            });
+        }
+        self.0.insert(variable.id().to_owned(), value.id().to_owned());
+        Ok(self)
+    }
+
+    /// Returns the configured filter.
+    pub fn build(self) -> Filter {
+        self.0.into()
+    }
+}
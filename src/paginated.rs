@@ -2,7 +2,13 @@
 
 use {
     std::{
+        collections::{
+            HashSet,
+            VecDeque
+        },
         iter::FusedIterator,
+        panic,
+        thread,
         vec
     },
     serde::{
@@ -10,19 +16,57 @@ use {
         de::DeserializeOwned
     },
     crate::{
+        Error,
         Result,
         client::{
             AnnotatedData,
-            Client
+            BASE_URL,
+            Client,
+            Link,
+            NoAuth,
+            Resource
         },
+        embed::Embeds,
         model::game
     }
 };
 
+/// The largest offset accepted by the API. Iterating past it returns `Error::PaginationLimitReached`. This number is made public for informational purposes only.
+pub const MAX_OFFSET: usize = 10_000;
+
+/// The direction in which a paginated list is sorted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Ascending order.
+    Asc,
+    /// Descending order.
+    Desc
+}
+
+impl Direction {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Asc => "asc",
+            Direction::Desc => "desc"
+        }
+    }
+}
+
+/// Implemented by the `OrderBy` enums of the endpoints which support sorting, associating each with the type of list it can sort. Used with `PaginatedList::order_by`.
+pub trait SortKey {
+    /// The type of the items of the lists which can be sorted by this key.
+    type Item: DeserializeOwned;
+
+    /// Returns the value of the `orderby` query parameter.
+    fn param_value(&self) -> &'static str;
+}
+
 #[derive(Debug, Deserialize)]
 struct PaginationInfo {
     max: u16,
-    size: u16
+    size: u16,
+    #[serde(default)]
+    links: Vec<Link>
 }
 
 #[derive(Debug, Deserialize)]
@@ -31,32 +75,111 @@ struct PaginatedResult<T> {
     pagination: PaginationInfo
 }
 
+/// The progress of a collection started using `PaginatedList::try_collect_with_progress`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollectProgress {
+    /// The number of pages which have been requested so far.
+    pub pages_fetched: usize,
+    /// The number of items which have been collected so far.
+    pub items: usize
+}
+
+type PageHandle<T> = thread::JoinHandle<Result<PaginatedResult<T>>>;
+type SpawnPage<T, A> = fn(Client<A>, String, Vec<(String, String)>) -> PageHandle<T>;
+
+/// Pages which are being requested in the background, configured using `PaginatedList::prefetch`.
+#[derive(Debug)]
+struct Prefetch<T, A> {
+    depth: usize,
+    /// Spawns a thread requesting a page. This is a function pointer so that the `Send` bound is only required by `PaginatedList::prefetch`.
+    spawn: SpawnPage<T, A>,
+    /// The pages being requested, starting at the list's current `prefix_len`.
+    pages: VecDeque<PageHandle<T>>,
+    next_offset: usize
+}
+
+fn spawn_page<T: DeserializeOwned + Send + 'static, A: Send + 'static>(client: Client<A>, uri: String, query: Vec<(String, String)>) -> PageHandle<T> {
+    thread::spawn(move || client.get_raw(&format!("{}{}", BASE_URL, uri), query))
+}
+
+/// The state of a list which continues in reverse after reaching `MAX_OFFSET`, configured using `PaginatedList::continue_in_reverse`.
+#[derive(Debug)]
+struct Continuation<T> {
+    /// Returns a key identifying an item. This is a function pointer so that the `Resource` bound is only required by `PaginatedList::continue_in_reverse`.
+    key: fn(&T) -> String,
+    /// The keys of the items requested before reversing.
+    seen: HashSet<String>,
+    reversed: bool
+}
+
 /// This iterator represents a list of items returned by the API in chunks of pages.
 ///
 /// # Errors
 ///
 /// All requests are performed lazily: accessing an item that's on a page which has not yet been loaded will cause an API request for that page. Accordingly, most iterator methods can return request errors.
 #[derive(Debug)]
-pub struct PaginatedList<T: DeserializeOwned> {
-    client: Client,
+pub struct PaginatedList<T: DeserializeOwned, A = NoAuth> {
+    client: Client<A>,
     prefix_len: usize,
     cached_prefix: vec::IntoIter<T>,
     end_seen: bool,
     page_size: u16,
+    pages_fetched: usize,
+    continuation: Option<Continuation<T>>,
+    prefetch: Option<Prefetch<T, A>>,
+    query: Vec<(String, String)>,
     uri: String
 }
 
-impl<T: DeserializeOwned> PaginatedList<T> {
-    pub(crate) fn new(client: Client, uri: String) -> PaginatedList<T> {
+impl<T: DeserializeOwned, A: Clone> PaginatedList<T, A> {
+    pub(crate) fn new(client: Client<A>, uri: String) -> PaginatedList<T, A> {
+        PaginatedList::with_query(client, uri, Vec::default())
+    }
+
+    pub(crate) fn with_query(client: Client<A>, uri: String, query: Vec<(String, String)>) -> PaginatedList<T, A> {
         PaginatedList {
-            client, uri,
+            client, query, uri,
             prefix_len: 0,
             cached_prefix: Vec::default().into_iter(),
             end_seen: false,
-            page_size: 20
+            page_size: 20,
+            pages_fetched: 0,
+            continuation: None,
+            prefetch: None
         }
     }
 
+    fn set_param(&mut self, key: &str, value: &str) {
+        self.discard_prefetched();
+        self.query.retain(|(existing_key, _)| existing_key != key);
+        self.query.push((key.to_owned(), value.to_owned()));
+    }
+
+    /// Configures the sort order of the items, replacing any order set by a search query.
+    ///
+    /// This should be called before iterating over the list, since items which have already been requested are not sorted again.
+    pub fn order_by(mut self, order_by: impl SortKey<Item = T>) -> PaginatedList<T, A> {
+        self.set_param("orderby", order_by.param_value());
+        self
+    }
+
+    /// Configures the sort direction of the items, replacing any direction set by a search query.
+    ///
+    /// This should be called before iterating over the list, since items which have already been requested are not sorted again.
+    pub fn direction(mut self, direction: Direction) -> PaginatedList<T, A> {
+        self.set_param("direction", direction.as_str());
+        self
+    }
+
+    /// Requests the given resources to be embedded in the responses for future requests.
+    ///
+    /// See the `embed` module for details.
+    pub fn set_embeds(&mut self, embeds: &Embeds) {
+        self.discard_prefetched();
+        self.query.retain(|(key, _)| key != "embed");
+        self.query.extend(embeds.query());
+    }
+
     /// Returns the number of elements per request.
     ///
     /// For most lists, this will be a number in `1..=200`. However, the list of all games can have a page size of up to 1000.
@@ -70,23 +193,198 @@ impl<T: DeserializeOwned> PaginatedList<T> {
     ///
     /// For the list of all games, panics if the given page size is not in `1..=1000`. For all other lists, panics if the given page size is not in `1..=200`.
     pub fn set_page_size(&mut self, page_size: u16) {
-        if &self.uri == game::LIST_URL {
-            if page_size < 1 || page_size > 1000 {
+        if self.uri == game::LIST_URL {
+            if !(1..=1000).contains(&page_size) {
                 panic!("argument for PaginatedList::set_page_size should be in 1..=1000, was {:?}", page_size);
             }
         } else {
-            if page_size < 1 || page_size > 200 {
+            if !(1..=200).contains(&page_size) {
                 panic!("argument for PaginatedList::set_page_size should be in 1..=200, was {:?}", page_size);
             }
         }
-        self.page_size = page_size.into();
+        self.discard_prefetched();
+        self.page_size = page_size;
+    }
+
+    /// Reverses the sort direction of the items, e.g. to iterate over runs newest first or over names from Z to A, without requesting the whole list.
+    ///
+    /// The API sorts in ascending order unless configured otherwise, so this sets the direction to descending unless it was already set to descending, e.g. by a search query or `direction`. Like `direction`, this should be called before iterating over the list, and has no effect for endpoints which don't support sorting.
+    pub fn rev_order(mut self) -> PaginatedList<T, A> {
+        self.reverse_direction();
+        self
+    }
+
+    fn reverse_direction(&mut self) {
+        let reversed = if self.query.iter().any(|(key, value)| key == "direction" && value == Direction::Desc.as_str()) { Direction::Asc } else { Direction::Desc };
+        self.set_param("direction", reversed.as_str());
+    }
+
+    /// Returns the offset of the next item, i.e. the number of items which have been yielded or skipped so far, including the offset this list was started at.
+    ///
+    /// This can be stored and passed to `starting_at` to resume iterating over an equivalent list later, e.g. after a long crawl was interrupted. Note that items added to or removed from the list in the meantime may shift the offsets.
+    pub fn current_offset(&self) -> usize {
+        self.prefix_len - self.cached_prefix.len()
+    }
+
+    /// Makes this list continue at the given offset, as previously returned by `current_offset`, discarding any items which have already been requested.
+    pub fn starting_at(mut self, offset: usize) -> PaginatedList<T, A> {
+        self.discard_prefetched();
+        self.cached_prefix = Vec::default().into_iter();
+        self.prefix_len = offset;
+        self.end_seen = false;
+        self
+    }
+
+    /// Skips the next `n` items without requesting the pages they're on.
+    ///
+    /// Items which have already been requested are skipped locally. If the skipped range extends beyond them, the next request starts directly at the new offset.
+    pub fn skip_items(&mut self, n: usize) {
+        let cached = self.cached_prefix.len();
+        if n <= cached {
+            self.cached_prefix.by_ref().take(n).for_each(drop);
+        } else {
+            self.discard_prefetched();
+            self.cached_prefix = Vec::default().into_iter();
+            self.prefix_len += n - cached;
+        }
+    }
+
+    /// Drops the pages being prefetched, e.g. because the query changed. Their threads still run to completion, but the results are ignored.
+    fn discard_prefetched(&mut self) {
+        if let Some(ref mut prefetch) = self.prefetch {
+            prefetch.pages.clear();
+        }
+    }
+
+    fn page_query(&self, offset: usize) -> Vec<(String, String)> {
+        self.query.iter()
+            .cloned()
+            .chain(vec![("offset".to_owned(), offset.to_string()), ("max".to_owned(), self.page_size.to_string())])
+            .collect()
+    }
+
+    fn fetch_page(&mut self) -> Result<PaginatedResult<T>> {
+        let mut prefetch = match self.prefetch.take() {
+            Some(prefetch) => prefetch,
+            None => return self.client.get_raw(&format!("{}{}", BASE_URL, self.uri), self.page_query(self.prefix_len))
+        };
+        if prefetch.pages.is_empty() { prefetch.next_offset = self.prefix_len; }
+        while prefetch.pages.len() < prefetch.depth && prefetch.next_offset <= MAX_OFFSET {
+            prefetch.pages.push_back((prefetch.spawn)(self.client.clone(), self.uri.clone(), self.page_query(prefetch.next_offset)));
+            prefetch.next_offset += usize::from(self.page_size);
+        }
+        let page = prefetch.pages.pop_front().expect("prefetch depth is at least 1").join().unwrap_or_else(|e| panic::resume_unwind(e));
+        match page {
+            Ok(ref page) if page.pagination.size >= page.pagination.max => {}
+            // the remaining pages are past the end, or at the wrong offsets if the request is retried
+            _ => prefetch.pages.clear()
+        }
+        self.prefetch = Some(prefetch);
+        page
+    }
+
+    /// Requests the page at the current `prefix_len` and advances past it. The items must be yielded by the caller.
+    fn next_page(&mut self) -> Result<PaginatedResult<T>> {
+        if self.prefix_len > MAX_OFFSET {
+            match self.continuation {
+                Some(ref mut continuation) if !continuation.reversed => continuation.reversed = true,
+                _ => return Err(Error::PaginationLimitReached)
+            }
+            self.reverse_direction();
+            self.prefix_len = 0;
+        }
+        let mut page = self.fetch_page()?;
+        assert_eq!(usize::from(page.pagination.size), page.data.len());
+        if page.pagination.size < page.pagination.max { self.end_seen = true; }
+        self.pages_fetched += 1;
+        self.prefix_len += usize::from(page.pagination.size);
+        if let Some(ref mut continuation) = self.continuation {
+            if continuation.reversed {
+                // stop once the reversed list reaches the items that were already requested
+                if let Some(overlap) = page.data.iter().position(|item| continuation.seen.contains(&(continuation.key)(item))) {
+                    page.data.truncate(overlap);
+                    self.end_seen = true;
+                }
+            } else {
+                continuation.seen.extend(page.data.iter().map(continuation.key));
+            }
+        }
+        Ok(page)
+    }
+
+    /// Returns an iterator over the remaining items which yields one `Page` per request instead of individual items, e.g. for inserting them into a database in batches.
+    ///
+    /// If some items of the current page have already been yielded by this list, the first page only contains the rest of them.
+    pub fn pages(self) -> Pages<T, A> {
+        Pages(self)
+    }
+
+    /// Requests all remaining items and collects them into a `Vec`, stopping at the first error.
+    ///
+    /// This is a shorthand for `collect::<Result<Vec<_>>>()`.
+    pub fn try_collect_all(self) -> Result<Vec<AnnotatedData<T, A>>> {
+        self.collect()
+    }
+
+    /// Like `try_collect_all`, but calls the given callback each time a page has been collected, for example to display a progress bar during long crawls.
+    pub fn try_collect_with_progress(mut self, mut callback: impl FnMut(CollectProgress)) -> Result<Vec<AnnotatedData<T, A>>> {
+        let mut items = Vec::default();
+        let mut pages_reported = self.pages_fetched;
+        loop {
+            let next = self.next().transpose()?;
+            let done = next.is_none();
+            items.extend(next);
+            if self.pages_fetched > pages_reported && self.cached_prefix.len() == 0 {
+                pages_reported = self.pages_fetched;
+                callback(CollectProgress {
+                    pages_fetched: self.pages_fetched,
+                    items: items.len()
+                });
+            }
+            if done { return Ok(items); }
+        }
+    }
+}
+
+impl<T: DeserializeOwned + Send + 'static, A: Clone + Send + 'static> PaginatedList<T, A> {
+    /// Requests up to the given number of pages ahead on background threads, so that iteration doesn't have to wait for the network as long as items are processed more slowly than pages arrive. `0` disables prefetching, which is the default.
+    ///
+    /// Prefetched requests share this list's client, so they respect the rate limit and are cached like any other request. Near the end of the list, up to `pages - 1` requests may be made for pages which turn out to be empty.
+    pub fn prefetch(mut self, pages: usize) -> PaginatedList<T, A> {
+        self.prefetch = if pages == 0 {
+            None
+        } else {
+            Some(Prefetch {
+                depth: pages,
+                spawn: spawn_page::<T, A>,
+                pages: VecDeque::default(),
+                next_offset: self.prefix_len
+            })
+        };
+        self
     }
 }
 
-impl<T: DeserializeOwned> Iterator for PaginatedList<T> {
-    type Item = Result<AnnotatedData<T>>;
+impl<T: Resource, A: Clone> PaginatedList<T, A> {
+    /// Makes this list continue from the other end once it reaches `MAX_OFFSET`, instead of returning `Error::PaginationLimitReached`. This allows iterating over lists with up to twice as many items as the API would normally allow.
+    ///
+    /// The items after the limit are yielded in reverse order, until reaching an item which was already yielded. Since the paths of all items before the limit are stored to detect this, this uses some memory. Items which are added to the list while iterating, or which the API doesn't sort consistently, e.g. because they're tied on the sort key, may be missed or yielded twice.
+    ///
+    /// Lists with more than twice `MAX_OFFSET` items still return `Error::PaginationLimitReached`. Since the API doesn't support filtering by date, such lists have to be split using other filters, e.g. requesting runs separately for each category.
+    pub fn continue_in_reverse(mut self) -> PaginatedList<T, A> {
+        self.continuation = Some(Continuation {
+            key: T::path,
+            seen: HashSet::default(),
+            reversed: false
+        });
+        self
+    }
+}
 
-    fn next(&mut self) -> Option<Result<AnnotatedData<T>>> {
+impl<T: DeserializeOwned, A: Clone> Iterator for PaginatedList<T, A> {
+    type Item = Result<AnnotatedData<T, A>>;
+
+    fn next(&mut self) -> Option<Result<AnnotatedData<T, A>>> {
         // first, try to take the next item from the cached prefix or page, this works because vec::IntoIter implements FusedIterator
         if let Some(next_inner) = self.cached_prefix.next() {
             return Some(Ok(self.client.annotate(next_inner)));
@@ -94,22 +392,85 @@ impl<T: DeserializeOwned> Iterator for PaginatedList<T> {
         // if the cache is empty and we've seen the end, we're done
         if self.end_seen { return None; }
         // if the cache is empty and we haven't seen the end, download and cache the next page
-        let PaginatedResult { data, pagination } = match self.client.get_raw(&self.uri, &[("offset", self.prefix_len.to_string()), ("max", self.page_size.to_string())]) {
+        let PaginatedResult { data, .. } = match self.next_page() {
             Ok(resp) => resp,
             Err(e) => { return Some(Err(e)); }
         };
-        assert_eq!(usize::from(pagination.size), data.len());
-        if pagination.size < pagination.max { self.end_seen = true; }
         self.cached_prefix = data.into_iter();
-        self.prefix_len += usize::from(pagination.size);
         // take the first element from the new page. If it's empty, we're done
         self.cached_prefix.next().map(|item| Ok(self.client.annotate(item)))
     }
 
+    /// Skips directly to the `n`th item using `skip_items`, so at most one request is made.
+    fn nth(&mut self, n: usize) -> Option<Result<AnnotatedData<T, A>>> {
+        self.skip_items(n);
+        self.next()
+    }
+
     fn size_hint(&self) -> (usize, Option<usize>) {
         let len = self.cached_prefix.len();
         (len, if self.end_seen { Some(len) } else { None })
     }
 }
 
-impl<T: DeserializeOwned> FusedIterator for PaginatedList<T> {}
+impl<T: DeserializeOwned, A: Clone> FusedIterator for PaginatedList<T, A> {}
+
+/// A page of items returned by the API in a single request, as yielded by `Pages`.
+#[derive(Debug, Clone)]
+pub struct Page<T, A = NoAuth> {
+    /// The offset of the first item of this page within the list.
+    pub offset: usize,
+    /// The number of items on this page.
+    pub size: usize,
+    /// The page size that was requested. If `size` is smaller than this, this is the last page.
+    pub max: usize,
+    /// The items on this page.
+    pub items: Vec<AnnotatedData<T, A>>,
+    /// The pagination links returned by the API, with the relations `"prev"` and `"next"` where applicable.
+    pub links: Vec<Link>
+}
+
+/// An iterator over the pages of a `PaginatedList`, returned by `PaginatedList::pages`.
+///
+/// # Errors
+///
+/// Like `PaginatedList`, each page is requested lazily and can return request errors.
+#[derive(Debug)]
+pub struct Pages<T: DeserializeOwned, A = NoAuth>(PaginatedList<T, A>);
+
+impl<T: DeserializeOwned, A: Clone> Iterator for Pages<T, A> {
+    type Item = Result<Page<T, A>>;
+
+    fn next(&mut self) -> Option<Result<Page<T, A>>> {
+        let list = &mut self.0;
+        // first, yield the rest of a page that was partially consumed by the list
+        if list.cached_prefix.len() > 0 {
+            let offset = list.current_offset();
+            let client = &list.client;
+            let items = list.cached_prefix.by_ref().map(|item| client.annotate(item)).collect::<Vec<_>>();
+            return Some(Ok(Page {
+                offset,
+                size: items.len(),
+                max: usize::from(list.page_size),
+                items,
+                links: Vec::default()
+            }));
+        }
+        if list.end_seen { return None; }
+        let offset = list.prefix_len;
+        let PaginatedResult { data, pagination } = match list.next_page() {
+            Ok(resp) => resp,
+            Err(e) => { return Some(Err(e)); }
+        };
+        if data.is_empty() { return None; }
+        Some(Ok(Page {
+            offset,
+            size: data.len(),
+            max: usize::from(pagination.max),
+            items: data.into_iter().map(|item| list.client.annotate(item)).collect(),
+            links: pagination.links
+        }))
+    }
+}
+
+impl<T: DeserializeOwned, A: Clone> FusedIterator for Pages<T, A> {}
@@ -0,0 +1,100 @@
+//! Downloading splits from [splits.io](https://splits.io/) for runs which link to them.
+//!
+//! This module is only available with the `splits` feature.
+
+use {
+    std::time::Duration,
+    serde::Deserialize,
+    crate::{
+        Error,
+        Result,
+        model::run::Run
+    }
+};
+
+static SPLITS_IO_HOST: &str = "splits.io";
+
+#[derive(Debug, Deserialize)]
+struct SplitsResponse {
+    run: SplitsData
+}
+
+#[derive(Debug, Deserialize)]
+struct SplitsData {
+    segments: Vec<SegmentData>
+}
+
+#[derive(Debug, Deserialize)]
+struct SegmentData {
+    name: String,
+    realtime_duration_ms: Option<u64>,
+    realtime_end_ms: Option<u64>,
+    #[serde(default)]
+    realtime_skipped: bool,
+    gametime_duration_ms: Option<u64>,
+    gametime_end_ms: Option<u64>,
+    #[serde(default)]
+    gametime_skipped: bool
+}
+
+/// A segment of a run's splits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    /// The segment's name, as given by the runner.
+    pub name: String,
+    /// The duration of this segment in real time. `None` if the segment was skipped or not timed in real time.
+    pub realtime: Option<Duration>,
+    /// The real time at the end of this segment, i.e. the split time. `None` if the segment was skipped or not timed in real time.
+    pub realtime_end: Option<Duration>,
+    /// The duration of this segment in game time. `None` if the segment was skipped or not timed in game time.
+    pub gametime: Option<Duration>,
+    /// The game time at the end of this segment, i.e. the split time. `None` if the segment was skipped or not timed in game time.
+    pub gametime_end: Option<Duration>
+}
+
+impl From<SegmentData> for Segment {
+    fn from(data: SegmentData) -> Segment {
+        fn time(ms: Option<u64>, skipped: bool) -> Option<Duration> {
+            // splits.io uses 0 for segments without a time
+            ms.filter(|&ms| !skipped && ms > 0).map(Duration::from_millis)
+        }
+
+        Segment {
+            realtime: time(data.realtime_duration_ms, data.realtime_skipped),
+            realtime_end: time(data.realtime_end_ms, data.realtime_skipped),
+            gametime: time(data.gametime_duration_ms, data.gametime_skipped),
+            gametime_end: time(data.gametime_end_ms, data.gametime_skipped),
+            name: data.name
+        }
+    }
+}
+
+/// The splits of a run, as returned by `Run::fetch_splits`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Splits {
+    /// The segments of the run, in order.
+    pub segments: Vec<Segment>
+}
+
+impl Run {
+    /// Downloads and parses this run's splits, if it has any. Returns `Ok(None)` if the run has no splits.
+    ///
+    /// The request is made through this run's client, so it is cached and subject to the same rate limiting as API requests.
+    ///
+    /// # Errors
+    ///
+    /// In addition to request errors, returns `Error::UnsupportedSplitsUri` if the splits are not hosted on splits.io.
+    pub fn fetch_splits(&self) -> Result<Option<Splits>> {
+        let uri = match self.splits_uri() {
+            Some(uri) => uri,
+            None => return Ok(None)
+        };
+        if uri.host_str() != Some(SPLITS_IO_HOST) { return Err(Error::UnsupportedSplitsUri(uri.clone())); }
+        // the API links to splits.io's deprecated v3 API, but only v4 includes segment names
+        let splits_id = uri.path_segments().and_then(|mut segments| segments.rfind(|segment| !segment.is_empty())).ok_or_else(|| Error::UnsupportedSplitsUri(uri.clone()))?;
+        let SplitsResponse { run } = self.client.get_raw(&format!("https://{}/api/v4/runs/{}", SPLITS_IO_HOST, splits_id), Vec::<(String, String)>::default())?;
+        Ok(Some(Splits {
+            segments: run.segments.into_iter().map(Segment::from).collect()
+        }))
+    }
+}
@@ -0,0 +1,325 @@
+//! A local mirror of selected games' runs and leaderboards, which can be updated incrementally.
+//!
+//! This module is only available with the `sync` feature. The mirror is stored in a [sled](https://docs.rs/sled) database.
+
+use {
+    std::{
+        collections::BTreeSet,
+        path::Path
+    },
+    chrono::prelude::*,
+    serde::{
+        Deserialize,
+        Serialize
+    },
+    serde_json::Value,
+    crate::{
+        Result,
+        archive,
+        client::Client,
+        model::{
+            leaderboard::{
+                Leaderboard,
+                LeaderboardOptions
+            },
+            run::Run,
+            variable::Filter
+        },
+        paginated::PaginatedList
+    }
+};
+
+/// The page size used when requesting runs. This is the maximum allowed by the API.
+const PAGE_SIZE: u16 = 200;
+
+/// The sync state of a game in the mirror.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct GameState {
+    /// Whether all of the game's runs have been downloaded.
+    #[serde(default)]
+    initialized: bool,
+    /// While the game is not initialized, the keys (as returned by `query_key`) of the run queries which have been downloaded completely.
+    #[serde(default)]
+    initial_queries_done: BTreeSet<String>,
+    /// The newest submission date seen so far.
+    last_submitted: Option<DateTime<Utc>>,
+    /// The newest verification date seen so far.
+    last_verified: Option<DateTime<Utc>>
+}
+
+impl GameState {
+    /// Returns the given queries of the initial sync, as returned by `archive::run_queries`, which haven't been downloaded completely yet.
+    fn pending_queries(&self, queries: Vec<Vec<(String, String)>>) -> Vec<Vec<(String, String)>> {
+        queries.into_iter()
+            .filter(|query| !self.initial_queries_done.contains(&query_key(query)))
+            .collect()
+    }
+}
+
+/// A summary of the changes made by `Mirror::sync`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SyncReport {
+    /// The number of runs which were added to the mirror or updated.
+    pub runs_updated: usize,
+    /// The number of leaderboards which were requested again because one of their category's runs changed.
+    pub leaderboards_updated: usize
+}
+
+/// A local store of the runs and full-game leaderboards of selected games.
+#[derive(Debug)]
+pub struct Mirror {
+    client: Client,
+    games: sled::Tree,
+    leaderboards: sled::Tree,
+    runs: sled::Tree
+}
+
+impl Mirror {
+    /// Opens the mirror stored at the given path, creating it if it doesn't exist.
+    pub fn open(client: impl Into<Client>, path: impl AsRef<Path>) -> Result<Mirror> {
+        let db = sled::open(path)?;
+        Ok(Mirror {
+            client: client.into(),
+            games: db.open_tree("games")?,
+            leaderboards: db.open_tree("leaderboards")?,
+            runs: db.open_tree("runs")?
+        })
+    }
+
+    /// Adds the game with the given API ID to the set of games kept in this mirror. Its data is downloaded on the next call to `sync`.
+    ///
+    /// Adding a game that's already in the mirror has no effect.
+    pub fn add_game(&self, game_id: &str) -> Result<()> {
+        if !self.games.contains_key(game_id)? {
+            self.games.insert(game_id, serde_json::to_vec(&GameState::default())?)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the API IDs of the games kept in this mirror.
+    pub fn game_ids(&self) -> Result<Vec<String>> {
+        self.games.iter().keys()
+            .map(|key| Ok(String::from_utf8_lossy(&key?).into_owned()))
+            .collect()
+    }
+
+    /// Updates all games in this mirror.
+    ///
+    /// The first sync of a game downloads all of its runs, one full-game category or level of a per-level category at a time, since the API only allows paginating through the first `MAX_OFFSET` runs of a query. Progress is saved after each of these, so an interrupted first sync is continued by the next call. After that, only runs submitted or verified since the previous sync are requested, and the leaderboards of the full-game categories of these runs are requested again.
+    ///
+    /// Runs which are rejected or deleted after having been downloaded are not detected by incremental syncs. To download a game's runs from scratch, remove it using `remove_game` and add it again.
+    pub fn sync(&self) -> Result<SyncReport> {
+        let fresh = self.client.fresh();
+        let mut report = SyncReport::default();
+        for game_id in self.game_ids()? {
+            let mut state = match self.games.get(&game_id)? {
+                Some(state) => serde_json::from_slice::<GameState>(&state)?,
+                None => continue
+            };
+            let categories = fresh.get::<_, Vec<Value>>(format!("/games/{}/categories?miscellaneous=yes", game_id))?;
+            if state.initialized {
+                let mut changed_categories = BTreeSet::default();
+                // new submissions
+                let newest_submitted = self.sync_runs(&fresh, &game_id, recent_runs_query(&game_id, None, "submitted"), submitted, state.last_submitted, &mut changed_categories, &mut report)?;
+                // runs verified since the last sync, which may have been submitted before it
+                let newest_verified = self.sync_runs(&fresh, &game_id, recent_runs_query(&game_id, Some("verified"), "verify-date"), verify_date, state.last_verified, &mut changed_categories, &mut report)?;
+                state.last_submitted = state.last_submitted.max(newest_submitted);
+                state.last_verified = state.last_verified.max(newest_verified);
+                self.sync_leaderboards(&fresh, &game_id, &categories, changed_categories, &mut report)?;
+            } else {
+                let game = fresh.get::<_, Value>(format!("/games/{}", game_id))?;
+                let levels = fresh.get::<_, Vec<Value>>(format!("/games/{}/levels", game_id))?;
+                for query in state.pending_queries(archive::run_queries(&game, &categories, &levels)) {
+                    let key = query_key(&query);
+                    let mut changed_categories = BTreeSet::default();
+                    let newest_submitted = self.sync_runs(&fresh, &game_id, query, submitted, None, &mut changed_categories, &mut report)?;
+                    state.last_submitted = state.last_submitted.max(newest_submitted);
+                    self.sync_leaderboards(&fresh, &game_id, &categories, changed_categories, &mut report)?;
+                    state.initial_queries_done.insert(key);
+                    self.games.insert(&game_id, serde_json::to_vec(&state)?)?;
+                }
+                state.initialized = true;
+                state.initial_queries_done.clear();
+                state.last_verified = self.runs_of(&game_id)?.iter().filter_map(verify_date).max();
+            }
+            self.games.insert(&game_id, serde_json::to_vec(&state)?)?;
+        }
+        self.games.flush()?;
+        Ok(report)
+    }
+
+    /// Requests the runs returned by the given query and stores them, and returns the newest date seen, as returned by `date`.
+    ///
+    /// If `since` is given, the query must be sorted by `date` in descending order, and requesting stops at the first run whose date is older than `since`. Runs without a date are skipped in this case.
+    #[allow(clippy::too_many_arguments)] // internal helper, bundling the arguments wouldn't make it clearer
+    fn sync_runs(&self, fresh: &Client, game_id: &str, query: Vec<(String, String)>, date: fn(&Value) -> Option<DateTime<Utc>>, since: Option<DateTime<Utc>>, changed_categories: &mut BTreeSet<String>, report: &mut SyncReport) -> Result<Option<DateTime<Utc>>> {
+        let mut newest = None;
+        let mut runs = PaginatedList::<Value>::with_query(fresh.clone(), "/runs".into(), query);
+        runs.set_page_size(PAGE_SIZE);
+        for run in runs {
+            let run = run?.into_data();
+            let date = date(&run);
+            if let Some(since) = since {
+                match date {
+                    Some(date) if date < since => break,
+                    Some(_) => {}
+                    // can't tell whether the run is new, and runs without a date were already downloaded by the initial sync
+                    None => continue
+                }
+            }
+            newest = newest.max(date);
+            if let (Some(run_id), Some(category_id)) = (run.get("id").and_then(Value::as_str), run.get("category").and_then(Value::as_str)) {
+                changed_categories.insert(category_id.to_owned());
+                self.runs.insert(run_key(game_id, run_id), serde_json::to_vec(&run)?)?;
+                report.runs_updated += 1;
+            }
+        }
+        Ok(newest)
+    }
+
+    /// Requests the full-game leaderboards of the given categories again and stores them.
+    ///
+    /// The game's categories are given as returned by the API, and are used to skip per-level categories, which have no full-game leaderboard.
+    fn sync_leaderboards(&self, fresh: &Client, game_id: &str, categories: &[Value], category_ids: BTreeSet<String>, report: &mut SyncReport) -> Result<()> {
+        for category_id in category_ids {
+            if is_per_level(categories, &category_id) { continue; }
+            let leaderboard = fresh.get_query::<_, _, _, _, Value>(format!("/leaderboards/{}/category/{}", game_id, category_id), LeaderboardOptions::default().query(&Filter::default()))?;
+            self.leaderboards.insert(leaderboard_key(game_id, &category_id), serde_json::to_vec(&leaderboard)?)?;
+            report.leaderboards_updated += 1;
+        }
+        Ok(())
+    }
+
+    fn runs_of(&self, game_id: &str) -> Result<Vec<Value>> {
+        self.runs.scan_prefix(run_key(game_id, ""))
+            .values()
+            .map(|run| Ok(serde_json::from_slice(&run?)?))
+            .collect()
+    }
+
+    /// Removes the given game and all of its data from this mirror.
+    pub fn remove_game(&self, game_id: &str) -> Result<()> {
+        for key in self.runs.scan_prefix(run_key(game_id, "")).keys() {
+            self.runs.remove(key?)?;
+        }
+        for key in self.leaderboards.scan_prefix(leaderboard_key(game_id, "")).keys() {
+            self.leaderboards.remove(key?)?;
+        }
+        self.games.remove(game_id)?;
+        Ok(())
+    }
+
+    /// Returns the mirrored full-game leaderboard of the category with the given API ID of the game with the given API ID, if any.
+    ///
+    /// The returned leaderboard is annotated with the mirror's client, so methods which request additional data still work.
+    pub fn leaderboard(&self, game_id: &str, category_id: &str) -> Result<Option<Leaderboard>> {
+        self.leaderboards.get(leaderboard_key(game_id, category_id))?
+            .map(|leaderboard| Ok(self.client.annotate(serde_json::from_slice(&leaderboard)?)))
+            .transpose()
+    }
+
+    /// Returns all mirrored runs of the game with the given API ID, regardless of status.
+    pub fn runs(&self, game_id: &str) -> Result<Vec<Run>> {
+        self.runs_of(game_id)?.into_iter()
+            .map(|run| Ok(self.client.annotate(serde_json::from_value(run)?)))
+            .collect()
+    }
+}
+
+fn run_key(game_id: &str, run_id: &str) -> String {
+    format!("{}/{}", game_id, run_id)
+}
+
+fn leaderboard_key(game_id: &str, category_id: &str) -> String {
+    format!("{}/{}", game_id, category_id)
+}
+
+/// Returns the query for the given game's runs with the given status, sorted by `order_by` in descending order.
+fn recent_runs_query(game_id: &str, status: Option<&str>, order_by: &str) -> Vec<(String, String)> {
+    status.map(|status| ("status".to_owned(), status.to_owned()))
+        .into_iter()
+        .chain(vec![
+            ("game".to_owned(), game_id.to_owned()),
+            ("orderby".to_owned(), order_by.to_owned()),
+            ("direction".to_owned(), "desc".to_owned())
+        ])
+        .collect()
+}
+
+/// Identifies one of the queries returned by `archive::run_queries` by its category and level.
+fn query_key(query: &[(String, String)]) -> String {
+    query.iter()
+        .filter(|(key, _)| key == "category" || key == "level")
+        .map(|(_, value)| &**value)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Returns whether the category with the given API ID is a per-level category, given the game's categories as returned by the API.
+fn is_per_level(categories: &[Value], category_id: &str) -> bool {
+    categories.iter().any(|category| category.get("id").and_then(Value::as_str) == Some(category_id) && category.get("type").and_then(Value::as_str) == Some("per-level"))
+}
+
+fn submitted(run: &Value) -> Option<DateTime<Utc>> {
+    run.get("submitted")?.as_str()?.parse().ok()
+}
+
+fn verify_date(run: &Value) -> Option<DateTime<Utc>> {
+    run.get("status")?.get("verify-date")?.as_str()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        serde_json::json,
+        super::*
+    };
+
+    fn categories() -> Vec<Value> {
+        vec![
+            json!({"id": "any", "type": "per-game"}),
+            json!({"id": "il", "type": "per-level"})
+        ]
+    }
+
+    fn queries() -> Vec<Vec<(String, String)>> {
+        let levels = vec![json!({"id": "level1"}), json!({"id": "level2"})];
+        archive::run_queries(&json!({"id": "game"}), &categories(), &levels)
+    }
+
+    #[test]
+    fn query_keys() {
+        assert_eq!(queries().iter().map(|query| query_key(query)).collect::<Vec<_>>(), vec!["any", "il/level1", "il/level2"]);
+    }
+
+    #[test]
+    fn recent_runs() {
+        let query = recent_runs_query("game", Some("verified"), "verify-date");
+        assert_eq!(query, vec![
+            ("status".to_owned(), "verified".to_owned()),
+            ("game".to_owned(), "game".to_owned()),
+            ("orderby".to_owned(), "verify-date".to_owned()),
+            ("direction".to_owned(), "desc".to_owned())
+        ]);
+        assert!(!recent_runs_query("game", None, "submitted").iter().any(|(key, _)| key == "status"));
+    }
+
+    #[test]
+    fn resume_initial_sync() -> Result<()> {
+        let mut state = GameState::default();
+        assert_eq!(state.pending_queries(queries()), queries());
+        state.initial_queries_done.insert(query_key(&queries()[0]));
+        state.initial_queries_done.insert(query_key(&queries()[2]));
+        // the progress is stored in the mirror between syncs
+        let state = serde_json::from_slice::<GameState>(&serde_json::to_vec(&state)?)?;
+        assert_eq!(state.pending_queries(queries()), vec![queries()[1].clone()]);
+        Ok(())
+    }
+
+    #[test]
+    fn per_level() {
+        assert!(!is_per_level(&categories(), "any"));
+        assert!(is_per_level(&categories(), "il"));
+        assert!(!is_per_level(&categories(), "unknown"));
+    }
+}
@@ -1,43 +1,221 @@
 use {
-    std::time::Duration,
-    bigdecimal::{
-        BigDecimal,
-        ToPrimitive,
-        Zero
+    std::{
+        cell::Cell,
+        collections::{
+            HashMap,
+            HashSet,
+            VecDeque
+        },
+        hash::Hash,
+        time::Duration
     },
-    lazy_static::lazy_static,
-    regex::Regex,
-    serde::Deserialize
+    serde::{
+        Deserialize,
+        Deserializer,
+        Serializer,
+        de::Error as _
+    },
+    reqwest::Url,
+    serde_json::{
+        Map,
+        Value
+    },
+    crate::{
+        Error,
+        format::{
+            format_iso8601,
+            parse_iso8601
+        }
+    }
 };
 
-lazy_static! {
-    static ref DURATION_RE: Regex = Regex::new("^PT(?:([0-9.]+)H)?(?:([0-9.]+)M)?(?:([0-9.]+)S)?$").unwrap();
+/// Options which affect how `*Data` types are deserialized, configured on the client using `Builder::strict` and `Builder::keep_raw_json`.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct DeserializeOptions {
+    pub(crate) strict: bool,
+    pub(crate) keep_raw_json: bool
+}
+
+thread_local! {
+    /// The options for the deserialization currently running on this thread. Set using `with_options`, since serde doesn't allow passing state into `Deserialize` implementations.
+    static OPTIONS: Cell<DeserializeOptions> = const { Cell::new(DeserializeOptions { strict: false, keep_raw_json: false }) };
+}
+
+/// Runs the given deserialization with the given options.
+pub(crate) fn with_options<T>(options: DeserializeOptions, f: impl FnOnce() -> T) -> T {
+    let prev = OPTIONS.with(|cell| cell.replace(options));
+    let result = f();
+    OPTIONS.with(|cell| cell.set(prev));
+    result
 }
 
-#[derive(Deserialize)]
-#[serde(remote = "Duration")]
-pub(crate) struct DurationDef(#[serde(getter = "unimplemented")] String);
+pub(crate) fn options() -> DeserializeOptions {
+    OPTIONS.with(Cell::get)
+}
 
-impl From<DurationDef> for Duration {
-    fn from(DurationDef(duration_string): DurationDef) -> Duration {
-        let captures = DURATION_RE.captures(&duration_string).expect("invalid ISO 8601 duration");
-        let hours = captures.get(1).map(|hours_match| hours_match.as_str().parse::<BigDecimal>().unwrap()).unwrap_or_else(BigDecimal::zero);
-        let minutes = captures.get(2).map(|mins_match| mins_match.as_str().parse::<BigDecimal>().unwrap()).unwrap_or_else(BigDecimal::zero);
-        let seconds = captures.get(3).map(|secs_match| secs_match.as_str().parse::<BigDecimal>().unwrap()).unwrap_or_else(BigDecimal::zero);
-        let total_secs = (hours * BigDecimal::from(60) + minutes) * BigDecimal::from(60) + seconds;
-        let nanos = (&total_secs % BigDecimal::from(1)) * BigDecimal::from(1_000_000_000);
-        Duration::new(total_secs.to_u64().expect("duration too long"), nanos.to_u32().unwrap())
+/// Deserializes the unknown fields of a `*Data` type, which are collected using `#[serde(flatten)]`.
+pub(crate) fn deserialize_extra<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Map<String, Value>, D::Error> {
+    let extra = Map::deserialize(deserializer)?;
+    if !extra.is_empty() && options().strict {
+        return Err(D::Error::custom(format_args!("unknown fields: {}", extra.keys().map(String::as_str).collect::<Vec<_>>().join(", "))));
     }
+    Ok(extra)
+}
+
+/// Adds `Error::Context` to the errors of composite operations.
+///
+/// The operation's name, e.g. `"Category::game"`, is declared as a `const OPERATION` at the top of the method, so all of its errors use the same name.
+pub(crate) trait ResultExt {
+    fn context(self, operation: &'static str) -> Self;
 }
 
-type OptDuration = Option<Duration>;
+impl<T> ResultExt for crate::Result<T> {
+    fn context(self, operation: &'static str) -> crate::Result<T> {
+        self.map_err(|source| Error::Context { operation, source: Box::new(source) })
+    }
+}
 
-#[derive(Deserialize)]
-#[serde(remote = "OptDuration")]
-pub(crate) struct OptDurationDef(#[serde(getter = "unimplemented")] Option<String>);
+/// Serializes a `Duration` as an ISO 8601 duration string, for use with `#[serde(with = "DurationDef")]`.
+pub(crate) enum DurationDef {}
 
-impl From<OptDurationDef> for Option<Duration> {
-    fn from(OptDurationDef(opt_duration): OptDurationDef) -> Option<Duration> {
-        opt_duration.map(|duration_string| DurationDef(duration_string).into())
+impl DurationDef {
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let duration_string = String::deserialize(deserializer)?;
+        parse_iso8601(&duration_string).ok_or_else(|| D::Error::custom(format_args!("invalid ISO 8601 duration: {:?}", duration_string)))
     }
+
+    pub(crate) fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format_iso8601(*duration))
+    }
+}
+
+/// Like `DurationDef`, but for an `Option<Duration>`, which is represented as `null` if absent.
+pub(crate) enum OptDurationDef {}
+
+impl OptDurationDef {
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+        #[derive(Deserialize)]
+        struct Wrapper(#[serde(with = "DurationDef")] Duration);
+
+        Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|Wrapper(duration)| duration))
+    }
+
+    pub(crate) fn serialize<S: Serializer>(opt_duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+        match opt_duration {
+            Some(duration) => serializer.serialize_some(&format_iso8601(*duration)),
+            None => serializer.serialize_none()
+        }
+    }
+}
+
+/// Remembers the keys seen during the last few polls, so pollers can report each item only once without keeping every key forever.
+///
+/// Keys are forgotten once they haven't been seen for `max_polls` polls, so the memory used only depends on how many items a poll returns.
+#[derive(Debug)]
+pub(crate) struct RecentlySeen<K> {
+    max_polls: usize,
+    polls: VecDeque<HashSet<K>>
 }
+
+impl<K: Eq + Hash> RecentlySeen<K> {
+    pub(crate) fn new(max_polls: usize) -> RecentlySeen<K> {
+        RecentlySeen {
+            max_polls,
+            polls: VecDeque::default()
+        }
+    }
+
+    /// Starts a new poll, forgetting the keys which weren't seen during the last `max_polls` polls.
+    pub(crate) fn start_poll(&mut self) {
+        self.polls.push_back(HashSet::default());
+        while self.polls.len() > self.max_polls {
+            self.polls.pop_front();
+        }
+    }
+
+    /// Records the given key as seen during the current poll. Returns `true` if it wasn't seen during any of the remembered polls.
+    pub(crate) fn insert(&mut self, key: K) -> bool {
+        let is_new = !self.polls.iter().any(|poll| poll.contains(&key));
+        if self.polls.is_empty() { self.start_poll(); }
+        self.polls.back_mut().expect("poll was just started").insert(key);
+        is_new
+    }
+}
+
+/// Percent-encodes the given string for use as a single segment of a URL path, e.g. a guest name, which can contain characters like `/` or `?`.
+pub(crate) fn path_segment(segment: &str) -> String {
+    let mut url = Url::parse("https://www.speedrun.com/").expect("base URL is valid");
+    url.path_segments_mut().expect("base URL can have path segments").pop_if_empty().push(segment);
+    url.path()[1..].to_owned()
+}
+
+/// Deserializes a map of boolean flags, treating flags which are `null` as absent, as well as treating a `null` map as empty.
+pub(crate) fn deserialize_flags<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashMap<String, bool>, D::Error> {
+    Ok(
+        Option::<HashMap<String, Option<bool>>>::deserialize(deserializer)?
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(flag, value)| value.map(|value| (flag, value)))
+            .collect()
+    )
+}
+
+/// Implements `PartialEq`, `Eq`, and `Hash` for an annotated model type by comparing the given key, usually the API ID.
+macro_rules! eq_by_key {
+    ($ty:ty, $key:ident) => {
+        #[doc = concat!("Compares by `", stringify!($key), "`, ignoring the rest of the cached data.")]
+        impl PartialEq for $ty {
+            fn eq(&self, other: &$ty) -> bool {
+                self.$key() == other.$key()
+            }
+        }
+
+        impl Eq for $ty {}
+
+        impl ::std::hash::Hash for $ty {
+            fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+                ::std::hash::Hash::hash(self.$key(), state);
+            }
+        }
+    };
+}
+
+pub(crate) use eq_by_key;
+
+/// Implements `Deserialize`, `Serialize`, and `Extensible` for a `*Data` type.
+///
+/// The type must derive `Deserialize` and `Serialize` with `#[serde(remote = "Self")]`, and have the fields `extra` (see `deserialize_extra`) and `raw`, which is skipped by serde. If `keep_raw_json` is enabled, the input is kept in `raw`.
+macro_rules! extensible {
+    ($ty:ty) => {
+        impl<'de> ::serde::Deserialize<'de> for $ty {
+            fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> ::std::result::Result<$ty, D::Error> {
+                if $crate::util::options().keep_raw_json {
+                    let raw = <::serde_json::Value as ::serde::Deserialize<'de>>::deserialize(deserializer)?;
+                    let mut data = <$ty>::deserialize(&raw).map_err(<D::Error as ::serde::de::Error>::custom)?;
+                    data.raw = Some(::std::sync::Arc::new(raw));
+                    Ok(data)
+                } else {
+                    <$ty>::deserialize(deserializer)
+                }
+            }
+        }
+
+        impl ::serde::Serialize for $ty {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error> {
+                <$ty>::serialize(self, serializer)
+            }
+        }
+
+        impl $crate::client::Extensible for $ty {
+            fn extra(&self) -> &::serde_json::Map<String, ::serde_json::Value> {
+                &self.extra
+            }
+
+            fn raw_json(&self) -> Option<&::serde_json::Value> {
+                self.raw.as_deref()
+            }
+        }
+    };
+}
+
+pub(crate) use extensible;
@@ -0,0 +1,147 @@
+//! Recording the client's API requests to a cassette file and replaying them later, configured using `Builder::record` and `Builder::replay`.
+
+use {
+    std::{
+        fs::File,
+        io::{
+            BufRead,
+            BufReader,
+            Write
+        },
+        path::Path,
+        sync::Mutex
+    },
+    reqwest::{
+        StatusCode,
+        Url
+    },
+    serde::{
+        Deserialize,
+        Deserializer,
+        Serialize,
+        Serializer,
+        de::Error as _
+    },
+    crate::{
+        Error,
+        Result
+    }
+};
+
+/// A request and its response, as stored in a cassette. Cassettes contain one interaction per line, in JSON format.
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct Interaction {
+    #[serde(with = "url_serde")]
+    url: Url,
+    #[serde(deserialize_with = "deserialize_status", serialize_with = "serialize_status")]
+    status: StatusCode,
+    body: String
+}
+
+/// Rejects invalid status codes when reading a cassette, so they're reported as an invalid cassette rather than when replaying.
+fn deserialize_status<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<StatusCode, D::Error> {
+    let status = u16::deserialize(deserializer)?;
+    StatusCode::from_u16(status).map_err(|_| D::Error::custom(format_args!("invalid HTTP status code: {}", status)))
+}
+
+fn serialize_status<S: Serializer>(status: &StatusCode, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_u16(status.as_u16())
+}
+
+#[derive(Debug)]
+pub(crate) enum Vcr {
+    Record(Mutex<File>),
+    /// The recorded interactions. Interactions which have been replayed are set to `None`.
+    Replay(Mutex<Vec<Option<Interaction>>>)
+}
+
+impl Vcr {
+    pub(crate) fn record(path: &Path) -> Result<Vcr> {
+        Ok(Vcr::Record(Mutex::new(File::create(path)?)))
+    }
+
+    pub(crate) fn replay(path: &Path) -> Result<Vcr> {
+        let interactions = BufReader::new(File::open(path)?).lines()
+            .map(|line| Ok(Some(serde_json::from_str(&line?)?)))
+            .collect::<Result<_>>()?;
+        Ok(Vcr::Replay(Mutex::new(interactions)))
+    }
+
+    pub(crate) fn is_replay(&self) -> bool {
+        matches!(self, Vcr::Replay(_))
+    }
+
+    /// Appends the given interaction to the cassette if recording.
+    pub(crate) fn on_response(&self, url: &Url, status: StatusCode, body: &str) -> Result<()> {
+        if let Vcr::Record(file) = self {
+            let mut file = file.lock().expect("cassette lock poisoned");
+            serde_json::to_writer(&mut *file, &Interaction { url: url.clone(), status, body: body.to_owned() })?;
+            writeln!(file)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the first recorded response for the given URL which hasn't been replayed yet, if replaying.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnexpectedRequest` if all responses for the given URL have already been replayed, or if there are none.
+    pub(crate) fn response(&self, url: &Url) -> Result<Option<(StatusCode, String)>> {
+        Ok(if let Vcr::Replay(interactions) = self {
+            let mut interactions = interactions.lock().expect("cassette lock poisoned");
+            let interaction = interactions.iter_mut()
+                .find(|interaction| interaction.as_ref().is_some_and(|interaction| interaction.url == *url))
+                .and_then(Option::take)
+                .ok_or_else(|| Error::UnexpectedRequest(url.clone()))?;
+            Some((interaction.status, interaction.body))
+        } else {
+            None
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        std::{
+            env,
+            fs,
+            path::PathBuf,
+            process
+        },
+        super::*
+    };
+
+    fn cassette_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("srcomapi-{}-{}.jsonl", name, process::id()))
+    }
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let path = cassette_path("round-trip");
+        let games = Url::parse("https://www.speedrun.com/api/v1/games?name=celeste").expect("valid URL");
+        let missing = Url::parse("https://www.speedrun.com/api/v1/games/missing").expect("valid URL");
+        let recorder = Vcr::record(&path)?;
+        recorder.on_response(&games, StatusCode::OK, r#"{"data":[]}"#)?;
+        recorder.on_response(&missing, StatusCode::NOT_FOUND, r#"{"status":404}"#)?;
+        drop(recorder);
+        let player = Vcr::replay(&path)?;
+        fs::remove_file(&path)?;
+        assert!(player.is_replay());
+        assert_eq!(player.response(&games)?, Some((StatusCode::OK, r#"{"data":[]}"#.to_owned())));
+        assert_eq!(player.response(&missing)?, Some((StatusCode::NOT_FOUND, r#"{"status":404}"#.to_owned())));
+        // each interaction is only replayed once
+        assert!(matches!(player.response(&games), Err(Error::UnexpectedRequest(ref url)) if *url == games));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_status() -> Result<()> {
+        let path = cassette_path("invalid-status");
+        fs::write(&path, "{\"url\":\"https://www.speedrun.com/api/v1/games\",\"status\":1000,\"body\":\"\"}\n")?;
+        let result = Vcr::replay(&path);
+        fs::remove_file(&path)?;
+        assert!(matches!(result, Err(Error::SerDe(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,272 @@
+//! A polling event subsystem for watching leaderboards, games, and users for new runs.
+//!
+//! Register the things you're interested in with a `Watcher`, then iterate over it (or use `Watcher::spawn` to get a channel) to receive `Event`s as they happen. The watcher takes care of bypassing stale cache entries, respecting the rate limit, and reporting each event only once.
+
+use {
+    std::{
+        collections::VecDeque,
+        sync::mpsc,
+        thread,
+        time::{
+            Duration,
+            SystemTime
+        }
+    },
+    crate::{
+        Result,
+        client::Client,
+        model::{
+            category::Category,
+            game::Game,
+            leaderboard::{
+                LeaderboardData,
+                LeaderboardOptions
+            },
+            level::Level,
+            run::{
+                OrderBy,
+                Run,
+                RunsQuery,
+                StatusFilter
+            },
+            user::User,
+            variable::Filter
+        },
+        paginated::Direction,
+        util::RecentlySeen
+    }
+};
+
+/// The number of most recent runs checked for each game or user target per poll.
+const RUNS_PER_POLL: u16 = 50;
+
+/// The number of polls for which an event is remembered after it was last found, so that it's not reported again.
+const SEEN_POLLS: usize = 10;
+
+/// An event reported by a `Watcher`.
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// A run was verified. For category targets, this is reported when a run first appears on the leaderboard.
+    NewVerifiedRun(Run),
+    /// A run took first place on a watched leaderboard. Tied world records are reported as well.
+    NewWorldRecord(Run),
+    /// A run of a watched game or user was rejected.
+    RunRejected(Run)
+}
+
+impl Event {
+    /// Returns the run this event is about.
+    pub fn run(&self) -> &Run {
+        match self {
+            Event::NewVerifiedRun(run) | Event::NewWorldRecord(run) | Event::RunRejected(run) => run
+        }
+    }
+
+    fn kind(&self) -> EventKind {
+        match self {
+            Event::NewVerifiedRun(_) => EventKind::NewVerifiedRun,
+            Event::NewWorldRecord(_) => EventKind::NewWorldRecord,
+            Event::RunRejected(_) => EventKind::RunRejected
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum EventKind {
+    NewVerifiedRun,
+    NewWorldRecord,
+    RunRejected
+}
+
+#[derive(Debug)]
+enum Target {
+    Category {
+        game_id: String,
+        /// `None` for full-game leaderboards.
+        level_id: Option<String>,
+        category_id: String,
+        filter: Filter
+    },
+    Runs(RunsQuery)
+}
+
+impl Target {
+    /// Returns the events that would be reported for the target's current state, without deduplication.
+    fn events(&self, client: &Client, fresh: &Client) -> Result<Vec<Event>> {
+        let mut events = Vec::default();
+        match self {
+            Target::Category { game_id, level_id, category_id, filter } => {
+                let path = if let Some(level_id) = level_id {
+                    format!("/leaderboards/{}/level/{}/{}", game_id, level_id, category_id)
+                } else {
+                    format!("/leaderboards/{}/category/{}", game_id, category_id)
+                };
+                let leaderboard = fresh.get_query::<_, _, _, _, LeaderboardData>(path, LeaderboardOptions::default().query(filter))?;
+                for entry in leaderboard.runs {
+                    let run = client.annotate(entry.run);
+                    events.push(Event::NewVerifiedRun(run.clone()));
+                    if entry.place == 1 {
+                        events.push(Event::NewWorldRecord(run));
+                    }
+                }
+            }
+            Target::Runs(query) => {
+                let verified = query.clone().status(StatusFilter::Verified).order_by(OrderBy::VerifyDate).direction(Direction::Desc);
+                let rejected = query.clone().status(StatusFilter::Rejected).order_by(OrderBy::Submitted).direction(Direction::Desc);
+                for (query, wrap) in [(verified, Event::NewVerifiedRun as fn(Run) -> Event), (rejected, Event::RunRejected)] {
+                    let mut runs = Run::search(fresh, query);
+                    runs.set_page_size(RUNS_PER_POLL);
+                    for run in runs.take(usize::from(RUNS_PER_POLL)) {
+                        events.push(wrap(client.annotate(run?.data)));
+                    }
+                }
+            }
+        }
+        Ok(events)
+    }
+}
+
+#[derive(Debug)]
+struct TargetState {
+    target: Target,
+    initialized: bool
+}
+
+/// Polls the API for changes to the registered targets and reports them as `Event`s.
+///
+/// When a target is polled for the first time, its current state is recorded without reporting any events, so that only changes after registration are reported.
+///
+/// Iterating over a watcher blocks until the next event is available, polling all targets at most once per configured interval. The iterator never ends.
+#[derive(Debug)]
+pub struct Watcher {
+    client: Client,
+    interval: Duration,
+    last_poll: Option<SystemTime>,
+    pending: VecDeque<Event>,
+    seen: RecentlySeen<(EventKind, String)>,
+    targets: Vec<TargetState>
+}
+
+impl Watcher {
+    /// Creates a new watcher without any targets which polls at most once per `interval`.
+    ///
+    /// Each poll makes at least one request per category target and two per game or user target, so the interval should be chosen with the rate limit in mind.
+    pub fn new(client: impl Into<Client>, interval: Duration) -> Watcher {
+        Watcher {
+            client: client.into(),
+            interval,
+            last_poll: None,
+            pending: VecDeque::default(),
+            seen: RecentlySeen::new(SEEN_POLLS),
+            targets: Vec::default()
+        }
+    }
+
+    fn add_target(&mut self, target: Target) {
+        self.targets.push(TargetState {
+            target,
+            initialized: false
+        });
+    }
+
+    /// Watches the leaderboard of the given full-game category, filtered by the given variable/value pairs, for new runs and world records.
+    ///
+    /// # Errors
+    ///
+    /// If the category's game can't be requested.
+    pub fn watch_category(&mut self, category: &Category, filter: &Filter) -> Result<()> {
+        let game_id = category.game()?.id().to_owned();
+        self.add_target(Target::Category {
+            game_id,
+            level_id: None,
+            category_id: category.id().to_owned(),
+            filter: filter.clone()
+        });
+        Ok(())
+    }
+
+    /// Watches the leaderboard of the given individual level in the given per-level category, filtered by the given variable/value pairs, for new runs and world records.
+    ///
+    /// # Errors
+    ///
+    /// If the level's game can't be requested.
+    pub fn watch_level(&mut self, level: &Level, category: &Category, filter: &Filter) -> Result<()> {
+        let game_id = level.game()?.id().to_owned();
+        self.add_target(Target::Category {
+            game_id,
+            level_id: Some(level.id().to_owned()),
+            category_id: category.id().to_owned(),
+            filter: filter.clone()
+        });
+        Ok(())
+    }
+
+    /// Watches the given game for newly verified and rejected runs.
+    pub fn watch_game(&mut self, game: &Game) {
+        self.add_target(Target::Runs(RunsQuery::default().game(game.id())));
+    }
+
+    /// Watches the given user for newly verified and rejected runs.
+    pub fn watch_user(&mut self, user: &User) {
+        self.add_target(Target::Runs(RunsQuery::default().user(user.id())));
+    }
+
+    /// Polls all targets once, without waiting for the interval, and returns the new events.
+    ///
+    /// Events which have already been reported are not reported again, even if they're found for a different target. If polling a target fails, the events found for the previous targets are returned by the next call or by the iterator.
+    pub fn poll(&mut self) -> Result<Vec<Event>> {
+        self.poll_pending()?;
+        Ok(self.pending.drain(..).collect())
+    }
+
+    fn poll_pending(&mut self) -> Result<()> {
+        self.last_poll = Some(self.client.clock().now());
+        let fresh = self.client.fresh();
+        self.seen.start_poll();
+        for state in &mut self.targets {
+            for event in state.target.events(&self.client, &fresh)? {
+                if self.seen.insert((event.kind(), event.run().id().to_owned())) && state.initialized {
+                    self.pending.push_back(event);
+                }
+            }
+            state.initialized = true;
+        }
+        Ok(())
+    }
+
+    /// Moves this watcher to a background thread and returns a channel on which its events are sent.
+    ///
+    /// The thread exits when the receiver is dropped.
+    pub fn spawn(self) -> mpsc::Receiver<Result<Event>> {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            for event in self {
+                if tx.send(event).is_err() { break; }
+            }
+        });
+        rx
+    }
+}
+
+impl Iterator for Watcher {
+    type Item = Result<Event>;
+
+    fn next(&mut self) -> Option<Result<Event>> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(Ok(event));
+            }
+            if let Some(last_poll) = self.last_poll {
+                let clock = self.client.clock();
+                // if the clock went backwards, wait for the full interval
+                let elapsed = clock.now().duration_since(last_poll).unwrap_or_default();
+                if elapsed < self.interval {
+                    clock.sleep(self.interval - elapsed);
+                }
+            }
+            if let Err(e) = self.poll_pending() {
+                return Some(Err(e));
+            }
+        }
+    }
+}